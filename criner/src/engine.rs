@@ -1,5 +1,6 @@
 use crate::{
     error::{Error, Result},
+    model::CrateVersion,
     persistence::{Db, TreeAccess},
     utils::*,
 };
@@ -40,7 +41,16 @@ async fn process_changes(
             move || {
                 let versions = db.open_crate_versions()?;
                 let krate = db.open_crates()?;
+                let rdeps = db.open_reverse_dependencies()?;
                 let context = db.context()?;
+                if !rdeps.is_complete()? {
+                    info!("Building reverse dependencies from all stored crate versions…");
+                    let num_versions = rdeps.rebuild_from(&versions)?;
+                    info!(
+                        "Stored reverse dependencies of {} crate versions",
+                        num_versions
+                    );
+                }
                 // NOTE: this loop can also be a stream, but that makes computation slower due to overhead
                 // Thus we just do this 'quickly' on the main thread, knowing that criner really needs its
                 // own executor or resources.
@@ -52,6 +62,7 @@ async fn process_changes(
                         versions.insert(&version)?;
                         context.update_today(|c| c.counts.crate_versions += 1)?;
                     }
+                    rdeps.insert_all(&CrateVersion::from(version))?;
                     if krate.upsert(&version)? {
                         context.update_today(|c| c.counts.crates += 1)?;
                     }
@@ -28,6 +28,12 @@ quick_error! {
         Bug(d: &'static str) {
             display("{}", d)
         }
+        Message(d: String) {
+            display("{}", d)
+        }
+        InvalidDump(d: String) {
+            display("{}", d)
+        }
         DeadlineExceeded(d: FormatDeadline) {
             display("Stopped computation as deadline was reached {}.", d)
         }
@@ -39,6 +45,10 @@ quick_error! {
             from()
             cause(err)
         }
+        RmpSerdeDecode(err: rmp_serde::decode::Error) {
+            from()
+            cause(err)
+        }
         Git2(err: git2::Error) {
             from()
             cause(err)
@@ -47,6 +57,14 @@ quick_error! {
             from()
             cause(err)
         }
+        Json(err: serde_json::Error) {
+            from()
+            cause(err)
+        }
+        Csv(err: csv::Error) {
+            from()
+            cause(err)
+        }
         Io(err: std::io::Error) {
             from()
             cause(err)
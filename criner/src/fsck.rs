@@ -0,0 +1,508 @@
+use crate::{
+    error::Result,
+    model::{
+        Context, Crate, CrateMetadata, CrateVersion, CrateVersionMetadata, DumpImport,
+        ReverseDependency,
+    },
+    persistence::{
+        crate_version_key, decode, CrateVersionsTree, CratesTree, Db, TreeAccess, CONTEXT_PREFIX,
+    },
+};
+use log::info;
+use serde::de::DeserializeOwned;
+use sled::IVec;
+use std::{fmt, path::Path};
+
+/// An inconsistency found in the database
+#[derive(Debug, Clone)]
+pub enum Issue {
+    /// The value stored at `key` in `tree` could not be decoded
+    Undecodable {
+        tree: &'static str,
+        key: String,
+        error: String,
+    },
+    /// A crate version is stored, but its crate doesn't list it
+    VersionNotInCrate { name: String, version: String },
+    /// A crate lists a version which isn't stored
+    MissingVersion { name: String, version: String },
+    /// The sum of all counts in the context history doesn't match the actual amount of stored items
+    CountMismatch {
+        what: &'static str,
+        recorded: u64,
+        actual: u64,
+    },
+}
+
+impl fmt::Display for Issue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use Issue::*;
+        match self {
+            Undecodable { tree, key, error } => write!(
+                f,
+                "Value of '{}' in tree '{}' could not be decoded: {}",
+                key, tree, error
+            ),
+            VersionNotInCrate { name, version } => write!(
+                f,
+                "Crate version '{}:{}' is not listed in its crate",
+                name, version
+            ),
+            MissingVersion { name, version } => write!(
+                f,
+                "Crate '{}' lists version '{}' which is not stored",
+                name, version
+            ),
+            CountMismatch {
+                what,
+                recorded,
+                actual,
+            } => write!(
+                f,
+                "The context records {} {}, but there are {}",
+                recorded, what, actual
+            ),
+        }
+    }
+}
+
+/// The result of checking a database
+#[derive(Debug, Default, Clone)]
+pub struct Report {
+    /// The amount of crates seen
+    pub crates: u64,
+    /// The amount of crate versions seen
+    pub crate_versions: u64,
+    /// The amount of issues found
+    pub issues: usize,
+    /// The amount of issues that could not be repaired, as the original data is affected.
+    /// Only set if repairs were requested.
+    pub unrepairable_issues: usize,
+    /// The amount of counts in the context which don't match the actual amount of stored items.
+    /// These are only corrected if requested explicitly, as doing so rewrites the context history.
+    pub count_mismatches: usize,
+    /// True if derived data was rebuilt.
+    pub repaired: bool,
+}
+
+struct Checker<F> {
+    on_issue: F,
+    report: Report,
+    undecodable_keys: Vec<(&'static str, IVec)>,
+}
+
+impl<F: FnMut(Issue)> Checker<F> {
+    fn issue(&mut self, issue: Issue) {
+        self.report.issues += 1;
+        (self.on_issue)(issue);
+    }
+
+    fn decode<T: DeserializeOwned>(
+        &mut self,
+        tree: &'static str,
+        key: &IVec,
+        value: &[u8],
+    ) -> Option<T> {
+        match rmp_serde::from_read(value) {
+            Ok(v) => Some(v),
+            Err(err) => {
+                if tree == CRATE_VERSIONS {
+                    self.report.unrepairable_issues += 1;
+                } else {
+                    self.undecodable_keys.push((tree, key.clone()));
+                }
+                self.issue(Issue::Undecodable {
+                    tree,
+                    key: String::from_utf8_lossy(key).into_owned(),
+                    error: err.to_string(),
+                });
+                None
+            }
+        }
+    }
+
+    fn check_decodes<T: DeserializeOwned>(&mut self, tree: &'static str, db: &Db) -> Result<()> {
+        for res in db.inner.open_tree(tree)?.iter() {
+            let (key, value) = res?;
+            self.decode::<T>(tree, &key, &value);
+        }
+        Ok(())
+    }
+
+    /// Check that each crate version is listed in its crate.
+    /// Versions of a crate are stored next to each other, which allows to hold only one crate at a time.
+    fn check_crate_versions(
+        &mut self,
+        versions: &CrateVersionsTree,
+        crates: &CratesTree,
+    ) -> Result<()> {
+        let mut current: Option<(String, Vec<String>)> = None;
+        for res in versions.tree().iter() {
+            let (key, value) = res?;
+            self.report.crate_versions += 1;
+            self.decode::<CrateVersion>(CRATE_VERSIONS, &key, &value);
+            let (name, version) = split_crate_version_key(&key);
+            match current {
+                Some((ref current_name, ref mut versions)) if *current_name == name => {
+                    versions.push(version)
+                }
+                _ => {
+                    if let Some((name, versions)) = current.take() {
+                        self.check_versions_in_crate(crates, name, versions)?;
+                    }
+                    current = Some((name, vec![version]));
+                }
+            }
+        }
+        if let Some((name, versions)) = current {
+            self.check_versions_in_crate(crates, name, versions)?;
+        }
+        Ok(())
+    }
+
+    fn check_versions_in_crate(
+        &mut self,
+        crates: &CratesTree,
+        name: String,
+        versions: Vec<String>,
+    ) -> Result<()> {
+        let known_versions = match crates.tree().get(&name)? {
+            Some(value) => decode::<Crate>(&value)
+                .map(|c| c.versions)
+                .unwrap_or_default(),
+            None => Vec::new(),
+        };
+        for version in versions {
+            if !known_versions.contains(&version) {
+                self.issue(Issue::VersionNotInCrate {
+                    name: name.clone(),
+                    version,
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Check that each version listed in a crate is stored
+    fn check_crates(&mut self, crates: &CratesTree, versions: &CrateVersionsTree) -> Result<()> {
+        for res in crates.tree().iter() {
+            let (key, value) = res?;
+            self.report.crates += 1;
+            let krate = match self.decode::<Crate>(CRATES, &key, &value) {
+                Some(krate) => krate,
+                None => continue,
+            };
+            let name = String::from_utf8_lossy(&key);
+            for version in krate.versions {
+                if !versions
+                    .tree()
+                    .contains_key(crate_version_key(&name, &version))?
+                {
+                    self.issue(Issue::MissingVersion {
+                        name: name.clone().into_owned(),
+                        version,
+                    });
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn check_context_totals(&mut self, db: &Db) -> Result<()> {
+        let mut total = Context::default();
+        for res in db.context()?.tree().scan_prefix(CONTEXT_PREFIX) {
+            let (key, value) = res?;
+            if let Some(context) = self.decode::<Context>(META, &key, &value) {
+                total = total + &context;
+            }
+        }
+        if total.counts.crate_versions != self.report.crate_versions {
+            self.report.count_mismatches += 1;
+            self.issue(Issue::CountMismatch {
+                what: "crate versions",
+                recorded: total.counts.crate_versions,
+                actual: self.report.crate_versions,
+            });
+        }
+        if total.counts.crates as u64 != self.report.crates {
+            self.report.count_mismatches += 1;
+            self.issue(Issue::CountMismatch {
+                what: "crates",
+                recorded: total.counts.crates as u64,
+                actual: self.report.crates,
+            });
+        }
+        Ok(())
+    }
+}
+
+const CRATES: &str = "crates";
+const CRATE_VERSIONS: &str = "crate_versions";
+const META: &str = "meta";
+
+fn split_crate_version_key(key: &[u8]) -> (String, String) {
+    let key = String::from_utf8_lossy(key);
+    let mut tokens = key.splitn(2, ':');
+    let name = tokens.next().unwrap_or_default().to_owned();
+    let version = tokens.next().unwrap_or_default().to_owned();
+    (name, version)
+}
+
+/// Recreate all crates from the stored crate versions, returning the amount of crates
+fn rebuild_crates(crates: &CratesTree, versions: &CrateVersionsTree) -> Result<u64> {
+    crates.tree().clear()?;
+    let mut count = 0;
+    let mut current: Option<(String, Crate)> = None;
+    let mut store = |name: String, mut krate: Crate| -> Result<()> {
+        krate.versions.sort();
+        crates.tree().insert(name, IVec::from(krate))?;
+        count += 1;
+        Ok(())
+    };
+    for key in versions.tree().iter().keys() {
+        let (name, version) = split_crate_version_key(&key?);
+        match current {
+            Some((ref current_name, ref mut krate)) if *current_name == name => {
+                krate.versions.push(version)
+            }
+            _ => {
+                if let Some((name, krate)) = current.take() {
+                    store(name, krate)?;
+                }
+                current = Some((
+                    name,
+                    Crate {
+                        versions: vec![version],
+                    },
+                ));
+            }
+        }
+    }
+    if let Some((name, krate)) = current {
+        store(name, krate)?;
+    }
+    Ok(count)
+}
+
+/// Change the counts of the most recent context entries so that their total matches the `actual` amount.
+fn correct_context_totals(
+    db: &Db,
+    actual: u64,
+    get: impl Fn(&Context) -> u64,
+    set: impl Fn(&mut Context, u64),
+) -> Result<()> {
+    let context = db.context()?;
+    let mut entries = Vec::new();
+    for res in context.tree().scan_prefix(CONTEXT_PREFIX) {
+        let (key, value) = res?;
+        entries.push((key, decode::<Context>(&value)?));
+    }
+    let recorded: u64 = entries.iter().map(|(_, c)| get(c)).sum();
+    if recorded < actual {
+        let mut missing = Context::default();
+        set(&mut missing, actual - recorded);
+        context.upsert(&missing)?;
+        return Ok(());
+    }
+    let mut excess = recorded - actual;
+    for (key, mut c) in entries.into_iter().rev() {
+        if excess == 0 {
+            break;
+        }
+        let count = get(&c);
+        let reduction = excess.min(count);
+        set(&mut c, count - reduction);
+        excess -= reduction;
+        context.tree().insert(key, IVec::from(c))?;
+    }
+    Ok(())
+}
+
+fn repair(
+    db: &Db,
+    undecodable_keys: Vec<(&'static str, IVec)>,
+    correct_context: bool,
+) -> Result<()> {
+    for (tree, key) in undecodable_keys {
+        info!(
+            "Removing undecodable value of '{}' in tree '{}'",
+            String::from_utf8_lossy(&key),
+            tree
+        );
+        db.inner.open_tree(tree)?.remove(key)?;
+    }
+    let versions = db.open_crate_versions()?;
+    info!("Rebuilding crates from crate versions…");
+    let num_crates = rebuild_crates(&db.open_crates()?, &versions)?;
+    info!("Rebuilding reverse dependencies from crate versions…");
+    db.open_reverse_dependencies()?.rebuild_from(&versions)?;
+    if !correct_context {
+        db.inner.flush()?;
+        return Ok(());
+    }
+    info!("Correcting context counts…");
+    correct_context_totals(
+        db,
+        versions.tree().len() as u64,
+        |c| c.counts.crate_versions,
+        |c, v| c.counts.crate_versions = v,
+    )?;
+    correct_context_totals(
+        db,
+        num_crates,
+        |c| c.counts.crates as u64,
+        |c, v| c.counts.crates = v as u32,
+    )?;
+    db.inner.flush()?;
+    Ok(())
+}
+
+/// Check the database at `db` for consistency, calling `on_issue` for each problem found.
+///
+/// Crate versions must be listed by their crate and vice versa, the counts recorded in the context must match
+/// the actual amount of crates and versions, and all values must be decodable.
+/// All trees are streamed, which keeps memory usage bounded no matter the size of the database.
+///
+/// If `repair` is set and issues were found, all data derived from crate versions is rebuilt and undecodable derived
+/// values are removed. Undecodable crate versions cannot be repaired.
+///
+/// Count mismatches in the context are only reported, unless `correct_context` is set as well. Correcting them rewrites
+/// history: missing counts are added to today's context, and excess counts are removed from the most recent days first.
+/// Databases written while the first update of each day was dropped typically undercount crate versions by one per day
+/// the engine ran.
+pub fn fsck(
+    db: impl AsRef<Path>,
+    repair: bool,
+    correct_context: bool,
+    on_issue: impl FnMut(Issue),
+) -> Result<Report> {
+    fsck_db(&Db::open(db)?, repair, correct_context, on_issue)
+}
+
+fn fsck_db(
+    db: &Db,
+    repair: bool,
+    correct_context: bool,
+    on_issue: impl FnMut(Issue),
+) -> Result<Report> {
+    let crates = db.open_crates()?;
+    let versions = db.open_crate_versions()?;
+    let mut checker = Checker {
+        on_issue,
+        report: Report::default(),
+        undecodable_keys: Vec::new(),
+    };
+
+    info!("Checking crate versions…");
+    checker.check_crate_versions(&versions, &crates)?;
+    info!("Checking crates…");
+    checker.check_crates(&crates, &versions)?;
+    info!("Checking context…");
+    checker.check_context_totals(db)?;
+    info!("Checking all other values…");
+    checker.check_decodes::<ReverseDependency>("reverse_dependencies", db)?;
+    checker.check_decodes::<CrateMetadata>("crate_metadata", db)?;
+    checker.check_decodes::<CrateVersionMetadata>("crate_version_metadata", db)?;
+    checker.check_decodes::<DumpImport>("dump_imports", db)?;
+
+    let Checker {
+        mut report,
+        undecodable_keys,
+        ..
+    } = checker;
+    let repairable_issues = if correct_context {
+        report.issues
+    } else {
+        report.issues - report.count_mismatches
+    };
+    if repair && repairable_issues > 0 {
+        self::repair(db, undecodable_keys, correct_context)?;
+        report.repaired = true;
+    }
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn version(name: &str, version: &str) -> crates_index_diff::CrateVersion {
+        crates_index_diff::CrateVersion {
+            name: name.into(),
+            kind: crates_index_diff::ChangeKind::Added,
+            version: version.into(),
+            checksum: String::new(),
+            features: Default::default(),
+            dependencies: Vec::new(),
+        }
+    }
+
+    fn inconsistent_db(path: &Path) -> Db {
+        let db = Db::open(path).unwrap();
+        let versions = db.open_crate_versions().unwrap();
+        let crates = db.open_crates().unwrap();
+        for v in &[
+            version("a", "1.0.0"),
+            version("a", "1.1.0"),
+            version("b", "0.1.0"),
+        ] {
+            versions.upsert(v).unwrap();
+        }
+        crates.upsert(&version("a", "1.0.0")).unwrap();
+        crates.upsert(&version("c", "3.0.0")).unwrap();
+        let mut context = Context::default();
+        context.counts.crate_versions = 5;
+        context.counts.crates = 2;
+        db.context().unwrap().upsert(&context).unwrap();
+        db.open_crate_metadata()
+            .unwrap()
+            .tree()
+            .insert("a", &b"garbage"[..])
+            .unwrap();
+        db
+    }
+
+    #[test]
+    fn issues_are_found_and_repaired_from_crate_versions() {
+        let tmp = tempfile::tempdir().unwrap();
+        let db = inconsistent_db(tmp.path());
+
+        let mut issues = Vec::new();
+        let report = fsck_db(&db, false, false, |i| issues.push(i.to_string())).unwrap();
+        assert_eq!(issues.len(), 5, "{:#?}", issues);
+        assert_eq!(
+            &issues[..4],
+            &[
+                "Crate version 'a:1.1.0' is not listed in its crate",
+                "Crate version 'b:0.1.0' is not listed in its crate",
+                "Crate 'c' lists version '3.0.0' which is not stored",
+                "The context records 5 crate versions, but there are 3",
+            ]
+        );
+        assert!(issues[4].starts_with("Value of 'a' in tree 'crate_metadata' could not be decoded"));
+        assert_eq!((report.crates, report.crate_versions), (2, 3));
+        assert_eq!(report.count_mismatches, 1);
+        assert!(!report.repaired);
+
+        let report = fsck_db(&db, true, false, |_| {}).unwrap();
+        assert!(report.repaired);
+        assert_eq!(report.unrepairable_issues, 0);
+
+        let mut issues = Vec::new();
+        let report = fsck_db(&db, true, false, |i| issues.push(i.to_string())).unwrap();
+        assert_eq!(
+            issues,
+            vec!["The context records 5 crate versions, but there are 3"],
+            "the context is left alone unless its correction is requested"
+        );
+        assert!(!report.repaired);
+
+        let report = fsck_db(&db, true, true, |_| {}).unwrap();
+        assert!(report.repaired);
+
+        let report = fsck_db(&db, false, false, |i| panic!("unexpected issue: {}", i)).unwrap();
+        assert_eq!((report.crates, report.crate_versions), (2, 3));
+        let a = Crate::from(db.open_crates().unwrap().tree().get("a").unwrap().unwrap());
+        assert_eq!(a.versions, vec!["1.0.0", "1.1.0"]);
+    }
+}
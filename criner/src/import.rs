@@ -0,0 +1,465 @@
+use crate::{
+    error::{Error, Result},
+    model::{Actor, ActorKind, CrateMetadata, CrateVersionMetadata},
+    persistence::{CratesTree, Db, TreeAccess},
+    utils::check,
+};
+use flate2::read::GzDecoder;
+use log::info;
+use serde::de::DeserializeOwned;
+use serde_derive::Deserialize;
+use std::{collections::HashMap, ffi::OsStr, fs::File, io::Read, path::Path, time::SystemTime};
+
+type Id = u32;
+
+#[derive(Deserialize)]
+struct CrateRow {
+    id: Id,
+    name: String,
+    downloads: u64,
+}
+
+#[derive(Deserialize)]
+struct VersionRow {
+    crate_id: Id,
+    num: String,
+    downloads: u64,
+}
+
+#[derive(Deserialize)]
+struct UserRow {
+    id: Id,
+    gh_login: String,
+    name: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct TeamRow {
+    id: Id,
+    login: String,
+    name: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct CrateOwnerRow {
+    crate_id: Id,
+    owner_id: Id,
+    owner_kind: u8,
+}
+
+#[derive(Deserialize)]
+struct KeywordRow {
+    id: Id,
+    keyword: String,
+}
+
+#[derive(Deserialize)]
+struct CrateKeywordRow {
+    crate_id: Id,
+    keyword_id: Id,
+}
+
+#[derive(Deserialize)]
+struct CategoryRow {
+    id: Id,
+    slug: String,
+}
+
+#[derive(Deserialize)]
+struct CrateCategoryRow {
+    crate_id: Id,
+    category_id: Id,
+}
+
+/// The amount of rows or crates after which the deadline is checked, and after which the import is checkpointed.
+const BATCH_SIZE: usize = 1000;
+
+/// The crates to import from a dump, along with all names their metadata refers to by id.
+/// Only these are kept in memory, all other rows are discarded while streaming through the tables.
+#[derive(Default)]
+struct Lookup {
+    crates: HashMap<Id, CrateMetadata>,
+    users: HashMap<Id, Actor>,
+    teams: HashMap<Id, Actor>,
+    keywords: HashMap<Id, String>,
+    categories: HashMap<Id, String>,
+    /// The amount of crates in the dump which are unknown to the database
+    skipped: usize,
+}
+
+fn open_archive(path: &Path) -> Result<tar::Archive<Box<dyn Read>>> {
+    let file = File::open(path)?;
+    let reader: Box<dyn Read> = if path.extension() == Some(OsStr::new("gz")) {
+        Box::new(GzDecoder::new(file))
+    } else {
+        Box::new(file)
+    };
+    Ok(tar::Archive::new(reader))
+}
+
+/// The name of the dump at `path`, which is the top-level directory in the archive, like `2020-02-28-020020`,
+/// or the file name of the archive if there is no such directory.
+fn dump_name(path: &Path) -> Result<String> {
+    let mut archive = open_archive(path)?;
+    for entry in archive.entries()? {
+        let entry = entry?;
+        let entry_path = entry.path()?;
+        if entry_path.components().count() > 1 {
+            if let Some(name) = entry_path
+                .components()
+                .next()
+                .and_then(|c| c.as_os_str().to_str())
+            {
+                return Ok(name.to_owned());
+            }
+        }
+    }
+    path.file_name()
+        .and_then(OsStr::to_str)
+        .map(ToOwned::to_owned)
+        .ok_or(Error::Bug("A path to a file always has a file name"))
+}
+
+/// Call `f` with the file name and contents of each table in the dump at `path`, in the order they are archived.
+fn for_each_table(path: &Path, mut f: impl FnMut(&str, &mut dyn Read) -> Result<()>) -> Result<()> {
+    let mut archive = open_archive(path)?;
+    for entry in archive.entries()? {
+        let mut entry = entry?;
+        let table = match entry.path()?.file_name().and_then(OsStr::to_str) {
+            Some(name) => name.to_owned(),
+            None => continue,
+        };
+        f(&table, &mut entry)?;
+    }
+    Ok(())
+}
+
+/// Call `f` with each row of the CSV table in `rows`, checking the `deadline` once per batch of rows.
+fn for_each_row<T: DeserializeOwned>(
+    rows: &mut dyn Read,
+    deadline: Option<SystemTime>,
+    mut f: impl FnMut(T) -> Result<()>,
+) -> Result<()> {
+    for (index, row) in csv::Reader::from_reader(rows).deserialize().enumerate() {
+        f(row?)?;
+        if index % BATCH_SIZE == 0 {
+            check(deadline)?;
+        }
+    }
+    Ok(())
+}
+
+/// Read all crates to import from the dump at `path`, along with the users, teams, keywords and categories.
+/// These are crates known to the database whose name sorts after `resume_after`, if set.
+///
+/// Crates are mandatory, all other tables may be missing and are then assumed to be empty.
+fn read_lookup(
+    path: &Path,
+    known_crates: &CratesTree,
+    resume_after: Option<&str>,
+    deadline: Option<SystemTime>,
+) -> Result<Lookup> {
+    let mut lookup = Lookup::default();
+    let mut has_crates = false;
+    for_each_table(path, |table, rows| {
+        match table {
+            "crates.csv" => {
+                has_crates = true;
+                for_each_row(rows, deadline, |c: CrateRow| {
+                    if resume_after.is_some_and(|last| c.name.as_str() <= last) {
+                        return Ok(());
+                    }
+                    if known_crates.tree().contains_key(&c.name)? {
+                        lookup.crates.insert(
+                            c.id,
+                            CrateMetadata {
+                                name: c.name,
+                                downloads: c.downloads,
+                                ..Default::default()
+                            },
+                        );
+                    } else {
+                        lookup.skipped += 1;
+                    }
+                    Ok(())
+                })?
+            }
+            "users.csv" => for_each_row(rows, deadline, |u: UserRow| {
+                lookup.users.insert(
+                    u.id,
+                    Actor {
+                        kind: ActorKind::User,
+                        login: u.gh_login,
+                        name: u.name,
+                    },
+                );
+                Ok(())
+            })?,
+            "teams.csv" => for_each_row(rows, deadline, |t: TeamRow| {
+                lookup.teams.insert(
+                    t.id,
+                    Actor {
+                        kind: ActorKind::Team,
+                        login: t.login,
+                        name: t.name,
+                    },
+                );
+                Ok(())
+            })?,
+            "keywords.csv" => for_each_row(rows, deadline, |k: KeywordRow| {
+                lookup.keywords.insert(k.id, k.keyword);
+                Ok(())
+            })?,
+            "categories.csv" => for_each_row(rows, deadline, |c: CategoryRow| {
+                lookup.categories.insert(c.id, c.slug);
+                Ok(())
+            })?,
+            _ => return Ok(()),
+        }
+        info!("Read table '{}' from dump", table);
+        Ok(())
+    })?;
+    if !has_crates {
+        return Err(Error::InvalidDump(format!(
+            "The dump at '{}' does not contain the 'crates.csv' table",
+            path.display()
+        )));
+    }
+    Ok(lookup)
+}
+
+/// Import download counts, owners, keywords and categories from a crates.io database dump at `dump`,
+/// a possibly gzipped tarball as obtained from <https://static.crates.io/db-dump.tar.gz>, into the database at `db`.
+///
+/// Only crates already known to the database are considered, which is why the engine should have run at least once.
+/// The import can be interrupted at any time and will resume where it left off when called with the same dump.
+/// If `deadline` is reached, the import stops with an error, and can be resumed later.
+///
+/// All tables are streamed, and only the crates to import are kept in memory. The archive is read twice, as the tables
+/// referring to crates may come before the crates themselves.
+pub fn import_dump(
+    db: impl AsRef<Path>,
+    dump: impl AsRef<Path>,
+    deadline: Option<SystemTime>,
+) -> Result<()> {
+    const USER: u8 = 0;
+    check(deadline)?;
+    let db = Db::open(db)?;
+    let dump = dump.as_ref();
+    let dump_name = dump_name(dump)?;
+    let imports = db.open_dump_imports()?;
+    let mut state = imports.get(&dump_name)?;
+    if state.finished {
+        info!("Dump '{}' was imported already", dump_name);
+        return Ok(());
+    }
+
+    info!(
+        "Reading crates.io database dump at '{}' - this can take a while…",
+        dump.display()
+    );
+    let Lookup {
+        mut crates,
+        users,
+        teams,
+        keywords,
+        categories,
+        skipped,
+    } = read_lookup(
+        dump,
+        &db.open_crates()?,
+        state.last_crate.as_deref(),
+        deadline,
+    )?;
+
+    let version_metadata = db.open_crate_version_metadata()?;
+    for_each_table(dump, |table, rows| {
+        match table {
+            "versions.csv" => for_each_row(rows, deadline, |v: VersionRow| {
+                if let Some(krate) = crates.get(&v.crate_id) {
+                    version_metadata.upsert(&CrateVersionMetadata {
+                        name: krate.name.clone(),
+                        version: v.num,
+                        downloads: v.downloads,
+                    })?;
+                }
+                Ok(())
+            })?,
+            "crate_owners.csv" => for_each_row(rows, deadline, |o: CrateOwnerRow| {
+                let owner = if o.owner_kind == USER {
+                    users.get(&o.owner_id)
+                } else {
+                    teams.get(&o.owner_id)
+                };
+                if let (Some(krate), Some(owner)) = (crates.get_mut(&o.crate_id), owner) {
+                    krate.owners.push(owner.clone());
+                }
+                Ok(())
+            })?,
+            "crates_keywords.csv" => for_each_row(rows, deadline, |ck: CrateKeywordRow| {
+                if let (Some(krate), Some(keyword)) =
+                    (crates.get_mut(&ck.crate_id), keywords.get(&ck.keyword_id))
+                {
+                    krate.keywords.push(keyword.clone());
+                }
+                Ok(())
+            })?,
+            "crates_categories.csv" => for_each_row(rows, deadline, |cc: CrateCategoryRow| {
+                if let (Some(krate), Some(category)) = (
+                    crates.get_mut(&cc.crate_id),
+                    categories.get(&cc.category_id),
+                ) {
+                    krate.categories.push(category.clone());
+                }
+                Ok(())
+            })?,
+            _ => return Ok(()),
+        }
+        info!("Imported table '{}' from dump", table);
+        Ok(())
+    })?;
+
+    let mut crates: Vec<_> = crates.into_values().collect();
+    crates.sort_by(|a, b| a.name.cmp(&b.name));
+    let crate_metadata = db.open_crate_metadata()?;
+    let mut stored = 0;
+    for batch in crates.chunks_mut(BATCH_SIZE) {
+        for krate in batch.iter_mut() {
+            krate.owners.sort_by(|a, b| a.login.cmp(&b.login));
+            krate.keywords.sort();
+            krate.categories.sort();
+            crate_metadata.upsert(krate)?;
+        }
+        stored += batch.len();
+        state.last_crate = batch.last().map(|krate| krate.name.clone());
+        imports.set(&dump_name, state.clone())?;
+        info!(
+            "Stored metadata of {} crates, skipped {} crates unknown to the database",
+            stored, skipped
+        );
+        check(deadline)?;
+    }
+
+    state.finished = true;
+    imports.set(&dump_name, state)?;
+    db.inner.flush()?;
+    info!(
+        "Finished importing dump '{}': stored metadata of {} crates, skipped {} crates unknown to the database",
+        dump_name, stored, skipped
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::persistence::wait_for_release;
+    use flate2::{write::GzEncoder, Compression};
+    use std::path::PathBuf;
+
+    const DUMP_NAME: &str = "2020-02-28-020020";
+
+    fn dump_archive(dir: &Path) -> PathBuf {
+        let archive_path = dir.join("db-dump.tar.gz");
+        let mut archive = tar::Builder::new(GzEncoder::new(
+            File::create(&archive_path).unwrap(),
+            Compression::fast(),
+        ));
+        archive
+            .append_dir_all(
+                DUMP_NAME,
+                Path::new(env!("CARGO_MANIFEST_DIR"))
+                    .join("tests/fixtures/db-dump")
+                    .join(DUMP_NAME),
+            )
+            .unwrap();
+        archive.into_inner().unwrap().finish().unwrap();
+        archive_path
+    }
+
+    fn db_with_crates(dir: &Path, names: &[&str]) -> PathBuf {
+        let db_path = dir.join("db");
+        let crates = Db::open(&db_path).unwrap().open_crates().unwrap();
+        for name in names {
+            crates
+                .upsert(&crates_index_diff::CrateVersion {
+                    name: name.to_string(),
+                    kind: crates_index_diff::ChangeKind::Added,
+                    version: "1.0.0".into(),
+                    checksum: String::new(),
+                    features: Default::default(),
+                    dependencies: Vec::new(),
+                })
+                .unwrap();
+        }
+        drop(crates);
+        wait_for_release(&db_path);
+        db_path
+    }
+
+    #[test]
+    fn import_stores_metadata_of_known_crates_only() {
+        let tmp = tempfile::tempdir().unwrap();
+        let db_path = db_with_crates(tmp.path(), &["clap", "crates-io-cli"]);
+        import_dump(&db_path, dump_archive(tmp.path()), None).unwrap();
+
+        wait_for_release(&db_path);
+        let db = Db::open(&db_path).unwrap();
+        let metadata = db.open_crate_metadata().unwrap();
+        let clap = CrateMetadata::from(metadata.tree().get("clap").unwrap().unwrap());
+        assert_eq!(clap.downloads, 1500);
+        assert_eq!(clap.keywords, vec!["cli", "parser"]);
+        assert_eq!(clap.categories, vec!["command-line-interface"]);
+        assert_eq!(
+            clap.owners,
+            vec![
+                Actor {
+                    kind: ActorKind::Team,
+                    login: "github:clap-rs:admins".into(),
+                    name: Some("Admins".into())
+                },
+                Actor {
+                    kind: ActorKind::User,
+                    login: "kbknapp".into(),
+                    name: Some("Kevin K.".into())
+                }
+            ]
+        );
+        assert!(metadata.tree().get("unknown-crate").unwrap().is_none());
+
+        let versions = db.open_crate_version_metadata().unwrap();
+        let clap_2 =
+            CrateVersionMetadata::from(versions.tree().get("clap:2.0.0").unwrap().unwrap());
+        assert_eq!(clap_2.downloads, 500);
+        assert_eq!(versions.tree().len(), 3);
+
+        let state = db.open_dump_imports().unwrap().get(DUMP_NAME).unwrap();
+        assert!(state.finished);
+    }
+
+    #[test]
+    fn import_resumes_after_the_last_stored_crate() {
+        let tmp = tempfile::tempdir().unwrap();
+        let db_path = db_with_crates(tmp.path(), &["clap", "crates-io-cli"]);
+        {
+            let db = Db::open(&db_path).unwrap();
+            db.open_dump_imports()
+                .unwrap()
+                .set(
+                    DUMP_NAME,
+                    crate::model::DumpImport {
+                        last_crate: Some("clap".into()),
+                        finished: false,
+                    },
+                )
+                .unwrap();
+        }
+        wait_for_release(&db_path);
+        import_dump(&db_path, dump_archive(tmp.path()), None).unwrap();
+
+        wait_for_release(&db_path);
+        let metadata = Db::open(&db_path).unwrap().open_crate_metadata().unwrap();
+        assert!(metadata.tree().get("clap").unwrap().is_none());
+        assert!(metadata.tree().get("crates-io-cli").unwrap().is_some());
+    }
+}
@@ -9,5 +9,16 @@ pub(crate) mod persistence;
 pub(crate) mod utils;
 
 mod engine;
+mod fsck;
+mod import;
+mod maintenance;
+mod query;
+mod serve;
 
 pub use engine::*;
+pub use fsck::{fsck, Issue, Report};
+pub use import::import_dump;
+pub use maintenance::{backup, compact, restore, Sizes};
+pub use model::{DependencyKind, ReverseDependency};
+pub use query::*;
+pub use serve::serve_blocking;
@@ -0,0 +1,247 @@
+use crate::{
+    error::{Error, Result},
+    persistence::Db,
+};
+use log::info;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+/// The size of a database on disk in bytes, before and after an operation
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Sizes {
+    pub before: u64,
+    pub after: u64,
+}
+
+/// The amount of bytes used by all files below `path`
+fn size_on_disk(path: &Path) -> Result<u64> {
+    let mut size = 0;
+    for entry in fs::read_dir(path)? {
+        let entry = entry?;
+        let meta = entry.metadata()?;
+        size += if meta.is_dir() {
+            size_on_disk(&entry.path())?
+        } else {
+            meta.len()
+        };
+    }
+    Ok(size)
+}
+
+fn ensure_does_not_exist(path: &Path) -> Result<()> {
+    if path.exists() {
+        return Err(Error::Message(format!(
+            "Refusing to overwrite '{}' - please move it out of the way first",
+            path.display()
+        )));
+    }
+    Ok(())
+}
+
+fn sibling(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_owned();
+    name.push(suffix);
+    path.with_file_name(name)
+}
+
+/// Write all trees of the database at `from` into a new database at `to`, using compression if `use_compression` is set,
+/// or as `from` does otherwise.
+/// sled's export is version and compression agnostic, which makes it suitable for migrations as well.
+fn export_into(from: &Path, to: &Path, use_compression: Option<bool>) -> Result<()> {
+    let from = Db::open(from)?;
+    let use_compression = match use_compression {
+        Some(c) => c,
+        None => from.uses_compression()?,
+    };
+    info!(
+        "Writing '{}' {} compression…",
+        to.display(),
+        if use_compression { "with" } else { "without" }
+    );
+    let to = Db::create_new(to, use_compression)?;
+    to.inner.import(from.inner.export());
+    to.set_uses_compression(use_compression)?;
+    to.inner.flush()?;
+    Ok(())
+}
+
+/// Write a consistent snapshot of the database at `db` into a new database at `dest`.
+///
+/// The database is locked for the duration of the backup, which must thus not be run alongside the engine.
+/// The backup is a fully functional database, and may be used as such.
+pub fn backup(db: impl AsRef<Path>, dest: impl AsRef<Path>) -> Result<Sizes> {
+    let (db, dest) = (db.as_ref(), dest.as_ref());
+    ensure_does_not_exist(dest)?;
+    let before = size_on_disk(db)?;
+    info!(
+        "Writing backup of '{}' to '{}'…",
+        db.display(),
+        dest.display()
+    );
+    export_into(db, dest, None)?;
+    Ok(Sizes {
+        before,
+        after: size_on_disk(dest)?,
+    })
+}
+
+/// Create the database at `db` from the backup at `backup`, which was created with `backup(…)`.
+///
+/// To protect existing data, `db` must not exist yet.
+pub fn restore(backup: impl AsRef<Path>, db: impl AsRef<Path>) -> Result<Sizes> {
+    let (backup, db) = (backup.as_ref(), db.as_ref());
+    ensure_does_not_exist(db)?;
+    let before = size_on_disk(backup)?;
+    info!("Restoring '{}' from '{}'…", db.display(), backup.display());
+    export_into(backup, db, None)?;
+    Ok(Sizes {
+        before,
+        after: size_on_disk(db)?,
+    })
+}
+
+/// Rewrite the database at `db` to reclaim unused space, turning compression on or off if `use_compression` is set.
+///
+/// The database is first written to `<db>.compacted` next to the original, which is then moved to `<db>.original`
+/// to make room for the compacted one, and removed once that is in place. If moving the compacted database fails,
+/// the original is moved back.
+///
+/// Should the process die in between, the original database can be recovered by moving `<db>.original` back
+/// to `<db>`, and `<db>.compacted` can be removed. Both must not exist for compaction to run again.
+pub fn compact(db: impl AsRef<Path>, use_compression: Option<bool>) -> Result<Sizes> {
+    let db = db.as_ref();
+    let (compacted, original) = (sibling(db, ".compacted"), sibling(db, ".original"));
+    ensure_does_not_exist(&compacted)?;
+    ensure_does_not_exist(&original)?;
+
+    let before = size_on_disk(db)?;
+    info!("Compacting '{}'…", db.display());
+    if let Err(err) = export_into(db, &compacted, use_compression) {
+        fs::remove_dir_all(&compacted).ok();
+        return Err(err);
+    }
+    if let Err(err) = fs::rename(db, &original) {
+        fs::remove_dir_all(&compacted).ok();
+        return Err(err.into());
+    }
+    if let Err(err) = fs::rename(&compacted, db) {
+        return Err(match fs::rename(&original, db) {
+            Ok(()) => err.into(),
+            Err(restore_err) => Error::Message(format!(
+                "Could not move the compacted database into place ({}), nor move the original back ({}) - please move '{}' to '{}' manually",
+                err,
+                restore_err,
+                original.display(),
+                db.display()
+            )),
+        });
+    }
+    fs::remove_dir_all(&original)?;
+    Ok(Sizes {
+        before,
+        after: size_on_disk(db)?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::persistence::{wait_for_release, TreeAccess};
+
+    fn db_with_crate_versions(path: &Path, count: usize) {
+        let db = Db::open(path).unwrap();
+        let versions = db.open_crate_versions().unwrap();
+        for v in 0..count {
+            versions
+                .upsert(&crates_index_diff::CrateVersion {
+                    name: "a".into(),
+                    kind: crates_index_diff::ChangeKind::Added,
+                    version: format!("1.0.{}", v),
+                    checksum: "0".repeat(64),
+                    features: Default::default(),
+                    dependencies: Vec::new(),
+                })
+                .unwrap();
+        }
+        db.inner.flush().unwrap();
+        drop((versions, db));
+        wait_for_release(path);
+    }
+
+    fn uses_compression(path: &Path) -> bool {
+        wait_for_release(path);
+        Db::open(path).unwrap().uses_compression().unwrap()
+    }
+
+    fn crate_versions_in(path: &Path) -> usize {
+        wait_for_release(path);
+        Db::open(path)
+            .unwrap()
+            .open_crate_versions()
+            .unwrap()
+            .tree()
+            .len()
+    }
+
+    #[test]
+    fn backup_and_restore_roundtrip() {
+        let tmp = tempfile::tempdir().unwrap();
+        let (db, backup_path, restored) = (
+            tmp.path().join("db"),
+            tmp.path().join("backup"),
+            tmp.path().join("restored"),
+        );
+        db_with_crate_versions(&db, 10);
+
+        let sizes = backup(&db, &backup_path).unwrap();
+        assert!(sizes.before > 0 && sizes.after > 0);
+        assert!(
+            backup(&db, &backup_path).is_err(),
+            "existing backups are not overwritten"
+        );
+
+        wait_for_release(&backup_path);
+        restore(&backup_path, &restored).unwrap();
+        assert!(
+            restore(&backup_path, &db).is_err(),
+            "existing databases are kept"
+        );
+        assert_eq!(crate_versions_in(&restored), 10);
+    }
+
+    #[test]
+    fn compaction_migrates_to_and_from_compression() {
+        let tmp = tempfile::tempdir().unwrap();
+        let db = tmp.path().join("db");
+        db_with_crate_versions(&db, 100);
+
+        compact(&db, Some(true)).unwrap();
+        assert!(uses_compression(&db));
+        assert_eq!(crate_versions_in(&db), 100);
+
+        wait_for_release(&db);
+        compact(&db, None).unwrap();
+        assert!(uses_compression(&db), "compression is retained by default");
+
+        wait_for_release(&db);
+        compact(&db, Some(false)).unwrap();
+        assert!(!uses_compression(&db));
+        assert_eq!(crate_versions_in(&db), 100);
+    }
+
+    #[test]
+    fn failed_compaction_leaves_the_database_in_place() {
+        let tmp = tempfile::tempdir().unwrap();
+        let db = tmp.path().join("db");
+        db_with_crate_versions(&db, 10);
+
+        let locked = Db::open(&db).unwrap();
+        assert!(compact(&db, Some(true)).is_err(), "the database is in use");
+        drop(locked);
+        assert!(!sibling(&db, ".compacted").exists());
+        assert!(!sibling(&db, ".original").exists());
+        assert_eq!(crate_versions_in(&db), 10);
+    }
+}
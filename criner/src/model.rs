@@ -77,10 +77,128 @@ pub struct CrateVersion {
     /// All cargo features
     pub features: HashMap<String, Vec<String>>,
     /// All crate dependencies
-    #[serde(rename = "deps")]
+    #[serde(rename = "deps", deserialize_with = "deserialize_dependencies")]
     pub dependencies: Vec<crates_index_diff::Dependency>,
 }
 
+/// Decode dependencies as serialized by `crates_index_diff::Dependency`, which omits `kind` and `package` if unset,
+/// but requires them when deserializing.
+fn deserialize_dependencies<'de, D>(
+    deserializer: D,
+) -> Result<Vec<crates_index_diff::Dependency>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    struct Dependency {
+        name: String,
+        #[serde(rename = "req")]
+        required_version: String,
+        features: Vec<String>,
+        optional: bool,
+        default_features: bool,
+        target: Option<String>,
+        #[serde(default)]
+        kind: Option<String>,
+        #[serde(default)]
+        package: Option<String>,
+    }
+
+    Ok(
+        <Vec<Dependency> as serde::Deserialize>::deserialize(deserializer)?
+            .into_iter()
+            .map(
+                |Dependency {
+                     name,
+                     required_version,
+                     features,
+                     optional,
+                     default_features,
+                     target,
+                     kind,
+                     package,
+                 }| crates_index_diff::Dependency {
+                    name,
+                    required_version,
+                    features,
+                    optional,
+                    default_features,
+                    target,
+                    kind,
+                    package,
+                },
+            )
+            .collect(),
+    )
+}
+
+impl CrateVersion {
+    /// Returns one `ReverseDependency` for each of our dependencies, with us being the dependent
+    pub fn reverse_dependencies(&self) -> impl Iterator<Item = ReverseDependency> + '_ {
+        self.dependencies.iter().map(move |d| ReverseDependency {
+            name: d.package.as_ref().unwrap_or(&d.name).to_owned(),
+            kind: d.kind.as_deref().into(),
+            dependent: self.name.clone(),
+            dependent_version: self.version.clone(),
+            required_version: d.required_version.clone(),
+            optional: d.optional,
+            yanked: self.kind == crates_index_diff::ChangeKind::Yanked,
+        })
+    }
+}
+
+/// The kind of a dependency, as declared in the `Cargo.toml` of the dependent
+#[derive(Debug, Serialize, Deserialize, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Default)]
+pub enum DependencyKind {
+    /// A dependency of the library or binaries, declared in `[dependencies]`
+    #[default]
+    Normal,
+    /// A dependency of tests, examples and benchmarks, declared in `[dev-dependencies]`
+    Dev,
+    /// A dependency of the build script, declared in `[build-dependencies]`
+    Build,
+}
+
+impl DependencyKind {
+    /// The name of the kind as used in the crates.io index
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DependencyKind::Normal => "normal",
+            DependencyKind::Dev => "dev",
+            DependencyKind::Build => "build",
+        }
+    }
+}
+
+impl From<Option<&str>> for DependencyKind {
+    fn from(kind: Option<&str>) -> Self {
+        match kind {
+            Some("dev") => DependencyKind::Dev,
+            Some("build") => DependencyKind::Build,
+            _ => DependencyKind::Normal,
+        }
+    }
+}
+
+/// A crate version depending on the crate `name`, as derived from the dependencies of all crate versions
+#[derive(Debug, Serialize, Deserialize, Default, Clone)]
+pub struct ReverseDependency {
+    /// The name of the crate which is depended on, i.e. `serde`.
+    pub name: String,
+    /// The kind of dependency
+    pub kind: DependencyKind,
+    /// The name of the crate which depends on `name`
+    pub dependent: String,
+    /// The version of the dependent crate
+    pub dependent_version: String,
+    /// The version requirement the dependent has on `name`, like `^1.0`
+    pub required_version: String,
+    /// True if the dependency is optional, i.e. enabled by a feature
+    pub optional: bool,
+    /// True if the dependent version was yanked
+    pub yanked: bool,
+}
+
 impl From<&crates_index_diff::CrateVersion> for CrateVersion {
     fn from(
         crates_index_diff::CrateVersion {
@@ -102,3 +220,58 @@ impl From<&crates_index_diff::CrateVersion> for CrateVersion {
         }
     }
 }
+
+/// The kind of actor owning a crate on crates.io
+#[derive(Debug, Serialize, Deserialize, Copy, Clone, Eq, PartialEq)]
+pub enum ActorKind {
+    /// A single person
+    User,
+    /// A team of a github organization
+    Team,
+}
+
+/// An owner of a crate, as known by crates.io
+#[derive(Debug, Serialize, Deserialize, Clone, Eq, PartialEq)]
+pub struct Actor {
+    /// Whether the owner is a user or a team
+    pub kind: ActorKind,
+    /// The github login of the user, or the name of the team, like `github:org:team`
+    pub login: String,
+    /// The human-readable name, if set
+    pub name: Option<String>,
+}
+
+/// Information about a crate which is only available from a crates.io database dump
+#[derive(Debug, Serialize, Deserialize, Default, Clone)]
+pub struct CrateMetadata {
+    /// The crate name, i.e. `clap`, as also used as key in the `crates` tree.
+    pub name: String,
+    /// The total amount of downloads across all versions
+    pub downloads: u64,
+    /// All owners of the crate
+    pub owners: Vec<Actor>,
+    /// All keywords of the crate, sorted alphabetically
+    pub keywords: Vec<String>,
+    /// The slugs of all categories the crate is in, sorted alphabetically
+    pub categories: Vec<String>,
+}
+
+/// Information about a crate version which is only available from a crates.io database dump
+#[derive(Debug, Serialize, Deserialize, Default, Clone)]
+pub struct CrateVersionMetadata {
+    /// The crate name, i.e. `clap`.
+    pub name: String,
+    /// The semantic version of the crate.
+    pub version: String,
+    /// The amount of downloads of this version
+    pub downloads: u64,
+}
+
+/// The state of importing a particular crates.io database dump, used to resume an interrupted import
+#[derive(Debug, Serialize, Deserialize, Default, Clone)]
+pub struct DumpImport {
+    /// The name of the last crate whose metadata was stored. Crates are imported in alphabetical order.
+    pub last_crate: Option<String>,
+    /// True if all crates of the dump have been imported
+    pub finished: bool,
+}
@@ -1,11 +1,33 @@
 use crate::model::CrateVersion;
 use crate::{
     error::{Error, Result},
-    model::{Context, Crate},
+    model::{Context, Crate, CrateMetadata, CrateVersionMetadata, DumpImport, ReverseDependency},
 };
+use log::warn;
+use serde::de::DeserializeOwned;
 use sled::{IVec, Tree};
 use std::{path::Path, time::SystemTime};
 
+/// sled releases the lock on a database in the background once its last handle was dropped.
+/// Block until that happened, so that tests can reopen a database they just closed.
+#[cfg(test)]
+pub fn wait_for_release(path: &Path) {
+    use fs2::FileExt;
+    let file = std::fs::File::open(path.join("db")).expect("database to exist");
+    file.lock_exclusive()
+        .expect("lock to be acquired eventually");
+    file.unlock().expect("lock to be released");
+}
+
+/// The prefix of all keys in the `meta` tree storing a `Context`
+pub const CONTEXT_PREFIX: &str = "context/";
+
+/// The key in the `meta` tree marking the reverse dependencies as complete
+const REVERSE_DEPENDENCIES_COMPLETE: &str = "reverse_dependencies/complete";
+
+/// The key in the `meta` tree marking a database as created with compression
+const USES_COMPRESSION: &str = "storage/uses_compression";
+
 #[derive(Clone)]
 pub struct Db {
     pub inner: sled::Db,
@@ -18,12 +40,66 @@ impl Db {
         // for our binary data as well.
         // TODO: re-evaluate that for textual data - it might enable us to store all files, and when we
         // have more read-based workloads. Maybe it's worth it to turn on.
-        // NOTE: Databases with and without compression need migration.
-        let inner = sled::Config::new().path(path).open()?;
+        // NOTE: sled refuses to open databases with another compression setting than the one they were created with,
+        // which we only know once the database is open. Those created with compression by `compact(…)`
+        // thus open on the second attempt.
+        let path = path.as_ref();
+        let open = |use_compression| {
+            sled::Config::new()
+                .path(path)
+                .use_compression(use_compression)
+                .open()
+        };
+        let inner = match open(false) {
+            Err(err @ sled::Error::Unsupported(_)) => open(true).map_err(|_| err)?,
+            res => res?,
+        };
+        Self::from_sled(inner)
+    }
+
+    /// Like `open(…)`, but fails if there is no database at `path` instead of creating an empty one.
+    pub fn open_existing(path: impl AsRef<Path>) -> Result<Db> {
+        let path = path.as_ref();
+        if !path.is_dir() {
+            return Err(Error::Message(format!(
+                "There is no database at '{}'",
+                path.display()
+            )));
+        }
+        Self::open(path)
+    }
+
+    /// Create a new and empty database at `path`, failing if it exists already.
+    /// It's up to the caller to record the compression setting with `set_uses_compression(…)`, as sled can only import
+    /// into empty databases.
+    pub fn create_new(path: impl AsRef<Path>, use_compression: bool) -> Result<Db> {
+        Self::from_sled(
+            sled::Config::new()
+                .path(path)
+                .use_compression(use_compression)
+                .create_new(true)
+                .open()?,
+        )
+    }
+
+    fn from_sled(inner: sled::Db) -> Result<Db> {
         let meta = inner.open_tree("meta")?;
         Ok(Db { inner, meta })
     }
 
+    /// Returns true if the database was created with compression.
+    /// Changing it requires a migration into a new database, see `compact(…)`.
+    pub fn uses_compression(&self) -> Result<bool> {
+        Ok(matches!(self.meta.get(USES_COMPRESSION)?, Some(v) if v.as_ref() == [1]))
+    }
+
+    /// Record whether the database uses compression, which is needed after importing another database into it.
+    pub fn set_uses_compression(&self, use_compression: bool) -> Result<()> {
+        self.meta
+            .insert(USES_COMPRESSION, &[use_compression as u8])?;
+        Ok(())
+    }
+
     pub fn open_crate_versions(&self) -> Result<CrateVersionsTree> {
         Ok(CrateVersionsTree {
             inner: self.inner.open_tree("crate_versions")?,
@@ -36,6 +112,31 @@ impl Db {
         })
     }
 
+    pub fn open_crate_metadata(&self) -> Result<CrateMetadataTree> {
+        Ok(CrateMetadataTree {
+            inner: self.inner.open_tree("crate_metadata")?,
+        })
+    }
+
+    pub fn open_crate_version_metadata(&self) -> Result<CrateVersionMetadataTree> {
+        Ok(CrateVersionMetadataTree {
+            inner: self.inner.open_tree("crate_version_metadata")?,
+        })
+    }
+
+    pub fn open_reverse_dependencies(&self) -> Result<ReverseDependenciesTree> {
+        Ok(ReverseDependenciesTree {
+            inner: self.inner.open_tree("reverse_dependencies")?,
+            meta: self.meta.clone(),
+        })
+    }
+
+    pub fn open_dump_imports(&self) -> Result<DumpImportsTree> {
+        Ok(DumpImportsTree {
+            inner: self.inner.open_tree("dump_imports")?,
+        })
+    }
+
     pub fn context(&self) -> Result<ContextTree> {
         Ok(ContextTree { inner: &self.meta })
     }
@@ -55,7 +156,9 @@ pub trait TreeAccess {
         existing_item: Option<Self::StorageItem>,
     ) -> Option<Self::StorageItem>;
 
-    /// Update an existing item, or create it as default, returning the stored item
+    /// Update an existing item, or a default one if there is none yet, returning the stored item.
+    ///
+    /// `f` is applied to the default item as well, so that the first update of a key is never lost.
     fn update(
         &self,
         key: impl AsRef<[u8]>,
@@ -63,14 +166,12 @@ pub trait TreeAccess {
     ) -> Result<Self::StorageItem> {
         self.tree()
             .update_and_fetch(key, |bytes: Option<&[u8]>| {
-                Some(match bytes {
-                    Some(bytes) => {
-                        let mut v = bytes.into();
-                        f(&mut v);
-                        v.into()
-                    }
-                    None => Self::StorageItem::default().into(),
-                })
+                let mut v = match bytes {
+                    Some(bytes) => bytes.into(),
+                    None => Self::StorageItem::default(),
+                };
+                f(&mut v);
+                Some(v.into())
             })?
             .map(From::from)
             .ok_or_else(|| Error::Bug("We always set a value"))
@@ -109,7 +210,8 @@ impl<'a> TreeAccess for ContextTree<'a> {
 
     fn key(&self, _item: &Self::InsertItem) -> Vec<u8> {
         format!(
-            "context/{}",
+            "{}{}",
+            CONTEXT_PREFIX,
             humantime::format_rfc3339(SystemTime::now())
                 .to_string()
                 .get(..10)
@@ -136,7 +238,7 @@ impl<'a> ContextTree<'a> {
 
     // NOTE: impl iterator is not allowed in traits unfortunately, but one could implement one manually
     pub fn iter(&self) -> impl DoubleEndedIterator<Item = Result<(String, Context)>> {
-        self.inner.iter().map(|r| {
+        self.inner.scan_prefix(CONTEXT_PREFIX).map(|r| {
             r.map(|(k, v)| {
                 (
                     String::from_utf8(k.as_ref().to_vec()).expect("utf8"),
@@ -206,11 +308,7 @@ impl TreeAccess for CrateVersionsTree {
     }
 
     fn key(&self, v: &crates_index_diff::CrateVersion) -> Vec<u8> {
-        let mut id = Vec::with_capacity(v.name.len() + v.version.len() + 1);
-        id.extend_from_slice(&v.name.as_bytes());
-        id.push(b':');
-        id.extend_from_slice(&v.version.as_bytes());
-        id
+        crate_version_key(&v.name, &v.version)
     }
 
     fn map_insert_return_value(&self, _v: IVec) -> Self::InsertResult {
@@ -226,6 +324,205 @@ impl TreeAccess for CrateVersionsTree {
     }
 }
 
+/// The key used for all trees storing information about a particular version of a crate
+pub fn crate_version_key(name: &str, version: &str) -> Vec<u8> {
+    let mut id = Vec::with_capacity(name.len() + version.len() + 1);
+    id.extend_from_slice(name.as_bytes());
+    id.push(b':');
+    id.extend_from_slice(version.as_bytes());
+    id
+}
+
+#[derive(Clone)]
+pub struct CrateMetadataTree {
+    inner: sled::Tree,
+}
+
+impl TreeAccess for CrateMetadataTree {
+    type StorageItem = CrateMetadata;
+    type InsertItem = CrateMetadata;
+    type InsertResult = ();
+
+    fn tree(&self) -> &Tree {
+        &self.inner
+    }
+
+    fn key(&self, item: &CrateMetadata) -> Vec<u8> {
+        item.name.clone().into_bytes()
+    }
+
+    fn map_insert_return_value(&self, _v: IVec) -> Self::InsertResult {}
+
+    fn merge(
+        &self,
+        new_item: &CrateMetadata,
+        _existing_item: Option<CrateMetadata>,
+    ) -> Option<CrateMetadata> {
+        // NOTE: Dumps are snapshots - the most recent one always wins
+        Some(new_item.clone())
+    }
+}
+
+#[derive(Clone)]
+pub struct CrateVersionMetadataTree {
+    inner: sled::Tree,
+}
+
+impl TreeAccess for CrateVersionMetadataTree {
+    type StorageItem = CrateVersionMetadata;
+    type InsertItem = CrateVersionMetadata;
+    type InsertResult = ();
+
+    fn tree(&self) -> &Tree {
+        &self.inner
+    }
+
+    fn key(&self, v: &CrateVersionMetadata) -> Vec<u8> {
+        crate_version_key(&v.name, &v.version)
+    }
+
+    fn map_insert_return_value(&self, _v: IVec) -> Self::InsertResult {}
+
+    fn merge(
+        &self,
+        new_item: &CrateVersionMetadata,
+        _existing_item: Option<CrateVersionMetadata>,
+    ) -> Option<CrateVersionMetadata> {
+        Some(new_item.clone())
+    }
+}
+
+/// A tree derived from all crate versions, allowing to find all versions depending on a given crate.
+/// Keys are `<name>:<kind>:<dependent>:<dependent-version>`, which groups all dependents of a crate by kind.
+#[derive(Clone)]
+pub struct ReverseDependenciesTree {
+    inner: sled::Tree,
+    meta: sled::Tree,
+}
+
+impl TreeAccess for ReverseDependenciesTree {
+    type StorageItem = ReverseDependency;
+    type InsertItem = ReverseDependency;
+    type InsertResult = ();
+
+    fn tree(&self) -> &Tree {
+        &self.inner
+    }
+
+    fn key(&self, d: &ReverseDependency) -> Vec<u8> {
+        let mut id = reverse_dependencies_prefix(&d.name);
+        id.extend_from_slice(d.kind.as_str().as_bytes());
+        id.push(b':');
+        id.extend_from_slice(&crate_version_key(&d.dependent, &d.dependent_version));
+        id
+    }
+
+    fn map_insert_return_value(&self, _v: IVec) -> Self::InsertResult {}
+
+    fn merge(
+        &self,
+        new_item: &ReverseDependency,
+        _existing_item: Option<ReverseDependency>,
+    ) -> Option<ReverseDependency> {
+        Some(new_item.clone())
+    }
+}
+
+fn reverse_dependencies_prefix(name: &str) -> Vec<u8> {
+    let mut prefix = Vec::with_capacity(name.len() + 1);
+    prefix.extend_from_slice(name.as_bytes());
+    prefix.push(b':');
+    prefix
+}
+
+impl ReverseDependenciesTree {
+    /// Store all dependencies of `version` as reverse dependencies, replacing previous information
+    pub fn insert_all(&self, version: &CrateVersion) -> Result<()> {
+        for rdep in version.reverse_dependencies() {
+            self.insert(&rdep)?;
+        }
+        Ok(())
+    }
+
+    /// Returns true if all reverse dependencies were rebuilt from the stored crate versions, after which
+    /// they are kept up to date with `insert_all(…)`.
+    pub fn is_complete(&self) -> Result<bool> {
+        Ok(self.meta.contains_key(REVERSE_DEPENDENCIES_COMPLETE)?)
+    }
+
+    /// Fail unless the tree `is_complete()`, to avoid answering queries with partial results.
+    pub fn ensure_complete(&self) -> Result<()> {
+        if self.is_complete()? {
+            Ok(())
+        } else {
+            Err(Error::Message(
+                "Reverse dependencies are incomplete - please run 'crates mine run' to complete them"
+                    .into(),
+            ))
+        }
+    }
+
+    /// Recreate all reverse dependencies from the given crate versions. Returns the amount of crate versions seen.
+    ///
+    /// Crate versions which cannot be decoded are skipped. The tree is marked complete only once all crate versions
+    /// were seen, so that an interrupted rebuild is started over.
+    pub fn rebuild_from(&self, versions: &CrateVersionsTree) -> Result<usize> {
+        self.meta.remove(REVERSE_DEPENDENCIES_COMPLETE)?;
+        self.inner.clear()?;
+        let mut count = 0;
+        for res in versions.tree().iter() {
+            let (key, value) = res?;
+            match decode::<CrateVersion>(&value) {
+                Ok(version) => self.insert_all(&version)?,
+                Err(err) => warn!(
+                    "Skipping crate version '{}' as it could not be decoded: {}",
+                    String::from_utf8_lossy(&key),
+                    err
+                ),
+            }
+            count += 1;
+        }
+        self.meta.insert(REVERSE_DEPENDENCIES_COMPLETE, &[][..])?;
+        Ok(count)
+    }
+
+    /// All versions depending on the crate `name`, ordered by kind of dependency
+    pub fn of(&self, name: &str) -> impl Iterator<Item = Result<ReverseDependency>> {
+        self.inner
+            .scan_prefix(reverse_dependencies_prefix(name))
+            .values()
+            .map(|r| r.map(ReverseDependency::from).map_err(Error::from))
+    }
+}
+
+/// Keeps track of the progress made when importing crates.io database dumps, keyed by the name of the dump
+#[derive(Clone)]
+pub struct DumpImportsTree {
+    inner: sled::Tree,
+}
+
+impl DumpImportsTree {
+    pub fn get(&self, dump_name: &str) -> Result<DumpImport> {
+        Ok(self
+            .inner
+            .get(dump_name)?
+            .map(DumpImport::from)
+            .unwrap_or_default())
+    }
+
+    pub fn set(&self, dump_name: &str, state: DumpImport) -> Result<()> {
+        self.inner
+            .insert(dump_name, IVec::from(state))
+            .map_err(Error::from)
+            .map(|_| ())
+    }
+}
+
+/// Decode a stored value, failing gracefully if that isn't possible, as opposed to the `From` implementations
+pub fn decode<T: DeserializeOwned>(bytes: &[u8]) -> Result<T> {
+    rmp_serde::from_read(bytes).map_err(Error::from)
+}
+
 macro_rules! impl_ivec_transform {
     ($ty:ty) => {
         impl From<&[u8]> for $ty {
@@ -251,3 +548,102 @@ macro_rules! impl_ivec_transform {
 impl_ivec_transform!(Crate);
 impl_ivec_transform!(CrateVersion);
 impl_ivec_transform!(Context);
+impl_ivec_transform!(CrateMetadata);
+impl_ivec_transform!(CrateVersionMetadata);
+impl_ivec_transform!(DumpImport);
+impl_ivec_transform!(ReverseDependency);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::DependencyKind;
+
+    fn version(
+        name: &str,
+        version: &str,
+        deps: &[(&str, Option<&str>, bool)],
+    ) -> crates_index_diff::CrateVersion {
+        crates_index_diff::CrateVersion {
+            name: name.into(),
+            kind: crates_index_diff::ChangeKind::Added,
+            version: version.into(),
+            checksum: String::new(),
+            features: Default::default(),
+            dependencies: deps
+                .iter()
+                .map(|(name, kind, optional)| crates_index_diff::Dependency {
+                    name: name.to_string(),
+                    required_version: "^1.0".into(),
+                    features: Vec::new(),
+                    optional: *optional,
+                    default_features: true,
+                    target: None,
+                    kind: kind.map(ToOwned::to_owned),
+                    package: None,
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn reverse_dependencies_are_rebuilt_from_stored_crate_versions() {
+        let tmp = tempfile::tempdir().unwrap();
+        let db = Db::open(tmp.path()).unwrap();
+        let versions = db.open_crate_versions().unwrap();
+        versions
+            .insert(&version(
+                "a",
+                "1.0.0",
+                &[
+                    ("serde", Some("normal"), true),
+                    ("serde_json", Some("dev"), false),
+                ],
+            ))
+            .unwrap();
+        versions
+            .insert(&version("b", "0.1.0", &[("serde", None, false)]))
+            .unwrap();
+        versions
+            .insert(&version("c", "2.0.0", &[("serde", Some("build"), false)]))
+            .unwrap();
+
+        let rdeps = db.open_reverse_dependencies().unwrap();
+        assert!(!rdeps.is_complete().unwrap());
+        assert_eq!(rdeps.rebuild_from(&versions).unwrap(), 3);
+        assert!(rdeps.is_complete().unwrap());
+        assert_eq!(
+            db.context().unwrap().iter().count(),
+            0,
+            "the marker is not mistaken for a context"
+        );
+
+        let serde = rdeps.of("serde").collect::<Result<Vec<_>>>().unwrap();
+        assert_eq!(
+            serde
+                .iter()
+                .map(|d| (d.kind, d.dependent.as_str(), d.optional))
+                .collect::<Vec<_>>(),
+            vec![
+                (DependencyKind::Build, "c", false),
+                (DependencyKind::Normal, "a", true),
+                (DependencyKind::Normal, "b", false),
+            ]
+        );
+        assert_eq!(rdeps.of("serde_json").count(), 1);
+    }
+
+    #[test]
+    fn the_first_update_of_the_day_is_counted() {
+        let tmp = tempfile::tempdir().unwrap();
+        let db = Db::open(tmp.path()).unwrap();
+        let context = db.context().unwrap();
+        let today = context
+            .update_today(|c| c.counts.crate_versions += 1)
+            .unwrap();
+        assert_eq!(today.counts.crate_versions, 1);
+        let today = context
+            .update_today(|c| c.counts.crate_versions += 2)
+            .unwrap();
+        assert_eq!(today.counts.crate_versions, 3);
+    }
+}
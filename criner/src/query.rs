@@ -0,0 +1,13 @@
+use crate::{error::Result, model::ReverseDependency, persistence::Db};
+use std::path::Path;
+
+/// Returns all crate versions depending on the crate `name`, ordered by the kind of dependency first,
+/// and the name and version of the dependent second.
+///
+/// The information is derived while running the engine, and is complete only after it ran at least once.
+/// Fails if it isn't complete yet, instead of returning partial results.
+pub fn reverse_dependencies(db: impl AsRef<Path>, name: &str) -> Result<Vec<ReverseDependency>> {
+    let rdeps = Db::open(db)?.open_reverse_dependencies()?;
+    rdeps.ensure_complete()?;
+    rdeps.of(name).collect()
+}
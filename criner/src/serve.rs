@@ -0,0 +1,282 @@
+use crate::{
+    error::{Error, Result},
+    model::{Context, Crate, CrateMetadata, CrateVersion, ReverseDependency},
+    persistence::{decode, Db, TreeAccess, CONTEXT_PREFIX},
+};
+use log::{info, warn};
+use serde::Serialize;
+use serde_derive::Serialize;
+use std::{net::ToSocketAddrs, path::Path};
+use tiny_http::{Header, Method, Request, Response, Server};
+
+#[derive(Serialize)]
+struct CrateResponse {
+    name: String,
+    versions: Vec<String>,
+    metadata: Option<CrateMetadata>,
+}
+
+#[derive(Serialize)]
+struct ContextResponse {
+    date: String,
+    context: Context,
+}
+
+#[derive(Serialize)]
+struct ErrorResponse {
+    error: String,
+}
+
+enum Reply {
+    Json(Vec<u8>),
+    NotFound(String),
+    MethodNotAllowed,
+}
+
+fn json(value: &impl Serialize) -> Result<Reply> {
+    Ok(Reply::Json(serde_json::to_vec(value)?))
+}
+
+fn crate_by_name(db: &Db, name: &str) -> Result<Option<CrateResponse>> {
+    let krate = match db.open_crates()?.tree().get(name)? {
+        Some(value) => decode::<Crate>(&value)?,
+        None => return Ok(None),
+    };
+    let metadata = db
+        .open_crate_metadata()?
+        .tree()
+        .get(name)?
+        .map(|v| decode::<CrateMetadata>(&v))
+        .transpose()?;
+    Ok(Some(CrateResponse {
+        name: name.to_owned(),
+        versions: krate.versions,
+        metadata,
+    }))
+}
+
+fn versions_of(db: &Db, name: &str) -> Result<Vec<CrateVersion>> {
+    db.open_crate_versions()?
+        .tree()
+        .scan_prefix(format!("{}:", name))
+        .values()
+        .map(|v| decode(&v?))
+        .collect()
+}
+
+fn context_history(db: &Db) -> Result<Vec<ContextResponse>> {
+    db.context()?
+        .iter()
+        .map(|r| {
+            r.map(|(key, context)| ContextResponse {
+                date: key.trim_start_matches(CONTEXT_PREFIX).to_owned(),
+                context,
+            })
+        })
+        .collect()
+}
+
+fn route(db: &Db, method: &Method, url: &str) -> Result<Reply> {
+    if *method != Method::Get {
+        return Ok(Reply::MethodNotAllowed);
+    }
+    let path = url.split('?').next().unwrap_or_default();
+    let segments: Vec<_> = path.trim_matches('/').split('/').collect();
+    match segments.as_slice() {
+        ["context"] => json(&context_history(db)?),
+        ["crates", name] => match crate_by_name(db, name)? {
+            Some(krate) => json(&krate),
+            None => Ok(Reply::NotFound(format!("Crate '{}' is unknown", name))),
+        },
+        ["crates", name, rest] => {
+            if !db.open_crates()?.tree().contains_key(name)? {
+                return Ok(Reply::NotFound(format!(
+                    "Crate '{}' is unknown, cannot show its {}",
+                    name, rest
+                )));
+            }
+            match *rest {
+                "versions" => json(&versions_of(db, name)?),
+                "rdeps" => {
+                    let rdeps = db.open_reverse_dependencies()?;
+                    rdeps.ensure_complete()?;
+                    json(&rdeps.of(name).collect::<Result<Vec<ReverseDependency>>>()?)
+                }
+                _ => Ok(Reply::NotFound(format!("There is nothing at '{}'", path))),
+            }
+        }
+        _ => Ok(Reply::NotFound(format!("There is nothing at '{}'", path))),
+    }
+}
+
+fn error_body(error: String) -> Vec<u8> {
+    serde_json::to_vec(&ErrorResponse { error }).expect("serialization to always succeed")
+}
+
+fn respond(db: &Db, request: Request) -> std::io::Result<()> {
+    let (status, body) = match route(db, request.method(), request.url()) {
+        Ok(Reply::Json(body)) => (200, body),
+        Ok(Reply::NotFound(msg)) => (404, error_body(msg)),
+        Ok(Reply::MethodNotAllowed) => (405, error_body("Only GET is supported".into())),
+        Err(err) => {
+            warn!("Failed to answer request for '{}': {}", request.url(), err);
+            (500, error_body(err.to_string()))
+        }
+    };
+    request.respond(
+        Response::from_data(body)
+            .with_status_code(status)
+            .with_header(
+                "Content-Type: application/json"
+                    .parse::<Header>()
+                    .expect("valid header"),
+            ),
+    )
+}
+
+/// Answer requests on `server` until it shuts down, one at a time.
+fn handle_requests(db: &Db, server: &Server) {
+    for request in server.incoming_requests() {
+        if let Err(err) = respond(db, request) {
+            warn!("Failed to send response: {}", err);
+        }
+    }
+}
+
+/// Serve the database at `db` read-only via HTTP on `bind`, answering with JSON.
+///
+/// The following endpoints are available:
+/// * `/crates/<name>` - all versions of a crate, along with metadata from a database dump if available
+/// * `/crates/<name>/versions` - all information about each version of a crate
+/// * `/crates/<name>/rdeps` - all crate versions depending on the crate
+/// * `/context` - the statistics gathered for each day the engine ran
+///
+/// Nothing is ever written, but as sled can't open databases in read-only mode without creating files,
+/// the database stays locked while serving, and can't be served while the engine runs.
+/// Serving a backup of the database avoids this limitation.
+/// It's an error if there is no database at `db`.
+pub fn serve_blocking(db: impl AsRef<Path>, bind: impl ToSocketAddrs) -> Result<()> {
+    let db = Db::open_existing(db)?;
+    let server = Server::http(bind).map_err(|err| Error::Message(err.to_string()))?;
+    info!("Serving on http://{}", server.server_addr());
+    handle_requests(&db, &server);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{Read, Write};
+    use std::net::{SocketAddr, TcpStream};
+
+    fn version(name: &str, version: &str, deps: &[&str]) -> crates_index_diff::CrateVersion {
+        crates_index_diff::CrateVersion {
+            name: name.into(),
+            kind: crates_index_diff::ChangeKind::Added,
+            version: version.into(),
+            checksum: String::new(),
+            features: Default::default(),
+            dependencies: deps
+                .iter()
+                .map(|name| crates_index_diff::Dependency {
+                    name: name.to_string(),
+                    required_version: "^1.0".into(),
+                    features: Vec::new(),
+                    optional: false,
+                    default_features: true,
+                    target: None,
+                    kind: None,
+                    package: None,
+                })
+                .collect(),
+        }
+    }
+
+    fn serve_test_db(path: &Path) -> SocketAddr {
+        let db = Db::open(path).unwrap();
+        {
+            let (crates, versions, rdeps) = (
+                db.open_crates().unwrap(),
+                db.open_crate_versions().unwrap(),
+                db.open_reverse_dependencies().unwrap(),
+            );
+            for v in &[
+                version("a", "1.0.0", &[]),
+                version("a", "1.1.0", &[]),
+                version("b", "0.1.0", &["a"]),
+            ] {
+                crates.upsert(v).unwrap();
+                versions.upsert(v).unwrap();
+            }
+            rdeps.rebuild_from(&versions).unwrap();
+            let mut context = Context::default();
+            context.counts.crate_versions = 3;
+            db.context().unwrap().insert(&context).unwrap();
+        }
+        let server = Server::http("127.0.0.1:0").unwrap();
+        let addr = server.server_addr();
+        std::thread::spawn(move || handle_requests(&db, &server));
+        addr
+    }
+
+    fn request(addr: SocketAddr, method: &str, path: &str) -> (u16, serde_json::Value) {
+        let mut stream = TcpStream::connect(addr).unwrap();
+        write!(
+            stream,
+            "{} {} HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n",
+            method, path
+        )
+        .unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        let status = response[9..12].parse().unwrap();
+        let body = &response[response.find("\r\n\r\n").unwrap() + 4..];
+        (status, serde_json::from_str(body).unwrap())
+    }
+
+    #[test]
+    fn endpoints_answer_with_json() {
+        let tmp = tempfile::tempdir().unwrap();
+        let addr = serve_test_db(tmp.path());
+
+        let (status, krate) = request(addr, "GET", "/crates/a");
+        assert_eq!(status, 200);
+        assert_eq!(krate["versions"], serde_json::json!(["1.0.0", "1.1.0"]));
+        assert!(krate["metadata"].is_null());
+
+        let (status, versions) = request(addr, "GET", "/crates/a/versions");
+        assert_eq!(status, 200);
+        assert_eq!(versions.as_array().unwrap().len(), 2);
+        assert_eq!(versions[1]["vers"], "1.1.0");
+
+        let (status, rdeps) = request(addr, "GET", "/crates/a/rdeps");
+        assert_eq!(status, 200);
+        assert_eq!(rdeps[0]["dependent"], "b");
+        assert_eq!(rdeps[0]["kind"], "Normal");
+
+        let (status, context) = request(addr, "GET", "/context");
+        assert_eq!(status, 200);
+        assert_eq!(context[0]["context"]["counts"]["crate_versions"], 3);
+    }
+
+    #[test]
+    fn unknown_resources_and_methods_are_rejected() {
+        let tmp = tempfile::tempdir().unwrap();
+        let addr = serve_test_db(tmp.path());
+
+        for path in &["/crates/unknown", "/crates/unknown/versions", "/nothing"] {
+            let (status, body) = request(addr, "GET", path);
+            assert_eq!(status, 404, "{}", path);
+            assert!(body["error"].is_string());
+        }
+        assert_eq!(request(addr, "POST", "/crates/a").0, 405);
+    }
+
+    #[test]
+    fn missing_databases_are_not_created() {
+        let tmp = tempfile::tempdir().unwrap();
+        let db = tmp.path().join("typo.db");
+        assert!(serve_blocking(&db, "127.0.0.1:0").is_err());
+        assert!(!db.exists());
+    }
+}
@@ -7,6 +7,7 @@ fn usage(c: &mut Criterion) {
         TreeOptions {
             initial_capacity: 10,
             message_buffer_capacity: 2,
+            ..TreeOptions::default()
         }
         .create()
     };
@@ -29,7 +30,7 @@ fn usage(c: &mut Criterion) {
         .bench_function("set tree 5 times", |b| {
             let root = small_tree();
             let mut progress = root.add_child("the one");
-            progress.init(Some(20), Some("element"));
+            progress.init(Some(20), Some("element".into()));
             b.iter(|| {
                 progress.set(1);
                 progress.set(2);
@@ -45,7 +46,7 @@ fn usage(c: &mut Criterion) {
             |b| {
                 let root = small_tree();
                 let mut progress = root.add_child("the one");
-                progress.init(Some(20), Some("element"));
+                progress.init(Some(20), Some("element".into()));
                 b.iter(|| {
                     progress.message(MessageLevel::Success, "for testing");
                 });
@@ -56,7 +57,7 @@ fn usage(c: &mut Criterion) {
         .bench_function("copy all messages with buffer being at capacity", |b| {
             let root = small_tree();
             let mut progress = root.add_child("the one");
-            progress.init(Some(20), Some("element"));
+            progress.init(Some(20), Some("element".into()));
             progress.done("foo");
             progress.done("bar");
             let mut out = Vec::new();
@@ -64,6 +65,48 @@ fn usage(c: &mut Criterion) {
                 root.copy_messages(&mut out);
             });
         });
+    c.benchmark_group("Tree::sorted_snapshot")
+        .throughput(Throughput::Elements(4))
+        .bench_function(
+            "take a snapshot of a tree of tasks, sampling their throughput",
+            |b| {
+                let root = small_tree();
+                let mut c = root.add_child("1");
+                let mut items = [c.add_child("1"), c.add_child("2"), c.add_child("3")];
+                for item in items.iter_mut() {
+                    item.init(Some(20), Some("element".into()));
+                }
+                let mut out = Vec::new();
+                let mut step = 0;
+                b.iter(|| {
+                    step += 1;
+                    items[0].set(step);
+                    root.sorted_snapshot(&mut out);
+                });
+            },
+        )
+        .throughput(Throughput::Elements(8))
+        .bench_function(
+            "take a snapshot of a tree of tasks nested 8 levels deep",
+            |b| {
+                let root = small_tree();
+                let mut items = vec![root.add_child("0")];
+                for level in 1..8 {
+                    let child = items.last_mut().unwrap().add_child(level.to_string());
+                    items.push(child);
+                }
+                for item in items.iter_mut() {
+                    item.init(Some(20), Some("element".into()));
+                }
+                let mut out = Vec::new();
+                let mut step = 0;
+                b.iter(|| {
+                    step += 1;
+                    items[7].set(step);
+                    root.sorted_snapshot(&mut out);
+                });
+            },
+        );
 }
 
 criterion_group!(benches, usage);
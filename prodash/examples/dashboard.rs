@@ -14,6 +14,7 @@ fn main() -> Result {
 async fn work_forever(pool: impl Spawn + Clone + Send + 'static, args: arg::Options) -> Result {
     let progress = prodash::TreeOptions {
         message_buffer_capacity: args.message_scrollback_buffer_size,
+        retention: Some(Duration::from_secs(2)),
         ..prodash::TreeOptions::default()
     }
     .create();
@@ -24,14 +25,14 @@ async fn work_forever(pool: impl Spawn + Clone + Send + 'static, args: arg::Opti
 
     loop {
         let local_work = new_chunk_of_work(
-            NestingLevel(thread_rng().gen_range(0, Key::max_level())),
+            NestingLevel(thread_rng().gen_range(0, MAX_LEVEL)),
             progress.clone(),
             pool.clone(),
             speed,
         );
         let pooled_work = (0..thread_rng().gen_range(6, 16usize)).map(|_| {
             pool.spawn_with_handle(new_chunk_of_work(
-                NestingLevel(thread_rng().gen_range(0, Key::max_level())),
+                NestingLevel(thread_rng().gen_range(0, MAX_LEVEL)),
                 progress.clone(),
                 pool.clone(),
                 speed,
@@ -71,6 +72,13 @@ fn launch_ambient_gui(
         tui::TuiOptions {
             title: TITLES.choose(&mut thread_rng()).map(|t| *t).unwrap().into(),
             frames_per_second: args.fps,
+            theme: match args.theme.as_deref() {
+                Some("light") => tui::Theme::light(),
+                Some("monochrome") => tui::Theme::monochrome(),
+                Some(_) => tui::Theme::dark(),
+                None => tui::Theme::from_env(),
+            },
+            show_summary: true,
             ..tui::TuiOptions::default()
         },
         futures::stream::select(
@@ -108,12 +116,12 @@ async fn work_item(mut progress: Item, speed: f32) -> () {
         if (max as usize % UNITS.len() + 1) == 0 {
             None
         } else {
-            UNITS.choose(&mut thread_rng()).map(|&s| s)
+            UNITS.choose(&mut thread_rng()).map(|&s| s.into())
         },
     );
 
     for step in 0..max {
-        progress.set(step as u32);
+        progress.set(step.into());
         let delay_ms = if thread_rng().gen_bool(CHANCE_TO_BLOCK_PER_STEP) {
             let eta = if thread_rng().gen_bool(CHANCE_TO_SHOW_ETA) {
                 Some(SystemTime::now().add(Duration::from_millis(LONG_WORK_DELAY_MS)))
@@ -128,7 +136,7 @@ async fn work_item(mut progress: Item, speed: f32) -> () {
         if thread_rng().gen_bool(0.01) {
             progress.init(
                 Some(max.into()),
-                UNITS.choose(&mut thread_rng()).map(|&s| s),
+                UNITS.choose(&mut thread_rng()).map(|&s| s.into()),
             )
         }
         if thread_rng().gen_bool(0.01) {
@@ -282,6 +290,9 @@ fn window_resize_stream(animate: bool) -> impl futures::Stream<Item = Event> {
         .boxed()
 }
 
+/// The deepest level of nesting of the tasks created by a chunk of work.
+const MAX_LEVEL: u8 = 6;
+
 struct NestingLevel(u8);
 type Result = std::result::Result<(), Box<dyn Error + Send>>;
 
@@ -308,6 +319,11 @@ mod arg {
         /// Defaults to 1.0. A valud of 0.5 halves the speed.
         #[argh(option, short = 's', default = "1.0")]
         pub speed_multitplier: f32,
+
+        /// the color theme, one of 'dark', 'light' or 'monochrome'.
+        /// Defaults to 'monochrome' if NO_COLOR is set, and to 'dark' otherwise.
+        #[argh(option)]
+        pub theme: Option<String>,
     }
 }
 
@@ -321,7 +337,6 @@ use futures::{
 use futures_timer::Delay;
 use prodash::{
     tree::Item,
-    tree::Key,
     tui::{self, ticker, Event, Line},
     Tree,
 };
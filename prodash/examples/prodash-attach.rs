@@ -0,0 +1,34 @@
+#![deny(unsafe_code)]
+fn main() -> Result<(), Box<dyn Error>> {
+    let args: Options = argh::from_env();
+    let follower = recording::attach(&args.socket)?;
+    let render = tui::render(
+        follower.root(),
+        tui::TuiOptions {
+            title: format!("attached to {}", args.socket.display()),
+            frames_per_second: args.fps,
+            ..tui::TuiOptions::default()
+        },
+    )?;
+    block_on(render);
+    // dropping the follower detaches, leaving the process sharing its progress unaffected.
+    drop(follower);
+    Ok(())
+}
+
+#[derive(FromArgs)]
+/// Show the progress of a running process in the terminal. Quitting detaches without affecting the process.
+struct Options {
+    /// the amount of frames to show per second.
+    #[argh(option, default = "10.0")]
+    fps: f32,
+
+    /// the Unix domain socket the process shares its progress on, with `prodash::recording::share(…)`.
+    #[argh(positional)]
+    socket: PathBuf,
+}
+
+use argh::FromArgs;
+use futures::executor::block_on;
+use prodash::{recording, tui};
+use std::{error::Error, path::PathBuf};
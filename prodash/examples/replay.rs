@@ -0,0 +1,42 @@
+#![deny(unsafe_code)]
+fn main() -> Result<(), Box<dyn Error>> {
+    let args: Options = argh::from_env();
+    let recording = Recording::read_from(BufReader::new(File::open(&args.recording)?))?;
+    let mut player = Player::new(recording);
+    player.set_speed(args.speed);
+    let replay = recording::replay(
+        player,
+        tui::TuiOptions {
+            title: format!("replay of {}", args.recording.display()),
+            frames_per_second: args.fps,
+            ..tui::TuiOptions::default()
+        },
+        futures::stream::pending(),
+    )?;
+    block_on(replay);
+    Ok(())
+}
+
+#[derive(FromArgs)]
+/// Replay a recording of a progress tree. Use space to pause, the arrow keys to seek and +/- to change the speed.
+struct Options {
+    /// the amount of frames to show per second.
+    #[argh(option, default = "10.0")]
+    fps: f32,
+
+    /// the initial speed of the replay, with 2.0 being twice as fast as the recording.
+    #[argh(option, short = 's', default = "1.0")]
+    speed: f32,
+
+    /// the recording to replay, as written by `prodash::recording::record(…)`.
+    #[argh(positional)]
+    recording: PathBuf,
+}
+
+use argh::FromArgs;
+use futures::executor::block_on;
+use prodash::{
+    recording::{self, Player, Recording},
+    tui,
+};
+use std::{error::Error, fs::File, io::BufReader, path::PathBuf};
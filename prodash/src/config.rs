@@ -1,16 +1,17 @@
 use crate::{
-    tree::{Item, Key, MessageRingBuffer},
+    tree::{Item, Key, MessageRingBuffer, Retention},
     Tree,
 };
 use dashmap::DashMap;
 use parking_lot::Mutex;
-use std::sync::Arc;
+use std::{sync::Arc, time::Duration};
 
 /// A way to configure new [`tree::Root`](./tree/struct.Root.html) instances
 /// ```rust
 /// use prodash::{Tree, TreeOptions};
 /// let tree = TreeOptions::default().create();
 /// let tree2 = TreeOptions { message_buffer_capacity: 100, ..TreeOptions::default() }.create();
+/// let tree3 = TreeOptions { retention: Some(std::time::Duration::from_secs(3)), ..TreeOptions::default() }.create();
 /// ```
 #[derive(Clone, Debug)]
 pub struct TreeOptions {
@@ -18,6 +19,13 @@ pub struct TreeOptions {
     pub initial_capacity: usize,
     /// The amount of messages we can hold before we start overwriting old ones
     pub message_buffer_capacity: usize,
+    /// If set, tasks stay in the tree for this long after their `Item` was dropped, along with their
+    /// [outcome](./tree/enum.Outcome.html), so that short tasks don't just flicker and what just happened stays visible.
+    ///
+    /// Tasks whose time is up are removed the next time an `Item` is added or dropped, or a snapshot is taken.
+    ///
+    /// If unset, tasks are removed as soon as their `Item` is dropped.
+    pub retention: Option<Duration>,
 }
 
 impl TreeOptions {
@@ -33,6 +41,7 @@ impl Default for TreeOptions {
         TreeOptions {
             initial_capacity: 100,
             message_buffer_capacity: 20,
+            retention: None,
         }
     }
 }
@@ -42,6 +51,7 @@ impl From<TreeOptions> for Tree {
         TreeOptions {
             initial_capacity,
             message_buffer_capacity,
+            retention,
         }: TreeOptions,
     ) -> Self {
         Tree {
@@ -52,7 +62,10 @@ impl From<TreeOptions> for Tree {
                 messages: Arc::new(Mutex::new(MessageRingBuffer::with_capacity(
                     message_buffer_capacity,
                 ))),
+                retention: retention.map(|duration| Arc::new(Retention::new(duration))),
+                outcome: None,
             })),
+            throughput: Some(Default::default()),
         }
     }
 }
@@ -0,0 +1,373 @@
+/*!
+* An HTTP server exposing the state of a [progress tree](../tree/struct.Root.html) as JSON, to watch it remotely, be it
+* from a browser or by scraping it into dashboards.
+*
+* The following endpoints are available:
+* * `/` - all tasks and messages, as `{"tasks": […], "messages": […]}`
+* * `/tasks` - all tasks in hierarchical order, as obtained by [`sorted_snapshot(…)`](../tree/struct.Root.html#method.sorted_snapshot)
+* * `/messages` - all messages from oldest to newest, as obtained by [`copy_messages(…)`](../tree/struct.Root.html#method.copy_messages)
+* * `/events` - a stream of [Server-Sent Events](https://html.spec.whatwg.org/multipage/server-sent-events.html),
+*   sending a `snapshot` event with the same data as `/` whenever it changed, checking once per `Options::interval`.
+*
+* Only `GET` requests are supported. The server never alters the progress tree.
+*
+* **Please note** that it is behind the `http-exporter` feature toggle, which is not enabled by default.
+*
+* # Example
+*
+* ```rust,no_run
+* # fn main() -> std::io::Result<()> {
+* use prodash::http;
+* let root = prodash::Tree::new();
+* let handle = http::serve(root.clone(), "127.0.0.1:8080", http::Options::default())?;
+* println!("Watch progress at http://{}/events", handle.addr());
+* // … make progress …
+* handle.shutdown_and_wait();
+* # Ok(())
+* # }
+* ```
+*/
+use crate::{
+    tree::{Key, Message, MessageLevel, ProgressState, ProgressStep, Root, Value},
+    unit::UnitDisplay,
+};
+use log::warn;
+use serde::Serialize;
+use serde_derive::Serialize;
+use std::{
+    collections::BTreeMap,
+    io::{self, Write},
+    net::{SocketAddr, ToSocketAddrs},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    thread,
+    time::{Duration, Instant, SystemTime},
+};
+use tiny_http::{Header, Method, Request, Response, Server};
+
+/// The longest time it takes for the server to notice it should stop.
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Configure the HTTP exporter
+#[derive(Clone, Debug)]
+pub struct Options {
+    /// The time between two checks for changes of the progress tree, for each client of the `/events` stream.
+    ///
+    /// If nothing changed, a comment is sent instead to keep the connection alive.
+    pub interval: Duration,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            interval: Duration::from_secs(1),
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct TaskResponse<'a> {
+    key: &'a [u64],
+    level: usize,
+    name: &'a str,
+    progress: Option<ProgressResponse>,
+    outcome: Option<String>,
+}
+
+#[derive(Serialize)]
+struct ProgressResponse {
+    step: ProgressStep,
+    done_at: Option<ProgressStep>,
+    fraction: Option<f32>,
+    display: String,
+    blocked: bool,
+    blocked_until: Option<String>,
+    per_second: Option<f32>,
+    eta: Option<String>,
+}
+
+#[derive(Serialize)]
+struct MessageResponse<'a> {
+    time: String,
+    level: &'static str,
+    origin: &'a str,
+    message: &'a str,
+    fields: BTreeMap<&'a str, &'a str>,
+}
+
+#[derive(Serialize)]
+struct SnapshotResponse<'a> {
+    tasks: Vec<TaskResponse<'a>>,
+    messages: Vec<MessageResponse<'a>>,
+}
+
+#[derive(Serialize)]
+struct ErrorResponse {
+    error: String,
+}
+
+fn format_time(time: SystemTime) -> String {
+    humantime::format_rfc3339_millis(time).to_string()
+}
+
+fn level_name(level: MessageLevel) -> &'static str {
+    use MessageLevel::*;
+    match level {
+        Debug => "debug",
+        Info => "info",
+        Warning => "warning",
+        Failure => "failure",
+        Success => "success",
+    }
+}
+
+fn tasks(entries: &[(Key, Value)]) -> Vec<TaskResponse<'_>> {
+    entries
+        .iter()
+        .map(|(key, value)| TaskResponse {
+            key: key.ids(),
+            level: key.level(),
+            name: &value.name,
+            progress: value.progress.as_ref().map(|p| ProgressResponse {
+                step: p.step,
+                done_at: p.done_at,
+                fraction: p.fraction(),
+                display: UnitDisplay::new(p.unit.as_ref(), p.step, p.done_at).to_string(),
+                blocked: matches!(p.state, ProgressState::Blocked(_)),
+                blocked_until: match p.state {
+                    ProgressState::Blocked(until) => until.map(format_time),
+                    ProgressState::Running => None,
+                },
+                per_second: p.throughput.map(|t| t.per_second()),
+                eta: p.throughput.and_then(|t| t.eta).map(format_time),
+            }),
+            outcome: value.outcome.map(|o| o.to_string()),
+        })
+        .collect()
+}
+
+fn messages(messages: &[Message]) -> Vec<MessageResponse<'_>> {
+    messages
+        .iter()
+        .map(|m| MessageResponse {
+            time: format_time(m.time),
+            level: level_name(m.level),
+            origin: &m.origin,
+            message: &m.message,
+            fields: m
+                .fields
+                .iter()
+                .map(|(k, v)| (k.as_str(), v.as_str()))
+                .collect(),
+        })
+        .collect()
+}
+
+/// The tasks and messages of a progress tree at one point in time.
+#[derive(Default)]
+struct Snapshot {
+    entries: Vec<(Key, Value)>,
+    messages: Vec<Message>,
+}
+
+impl Snapshot {
+    fn update(&mut self, progress: &Root) {
+        progress.sorted_snapshot(&mut self.entries);
+        progress.copy_messages(&mut self.messages);
+    }
+
+    fn to_json(&self) -> serde_json::Result<Vec<u8>> {
+        serde_json::to_vec(&SnapshotResponse {
+            tasks: tasks(&self.entries),
+            messages: messages(&self.messages),
+        })
+    }
+}
+
+enum Reply {
+    Json(Vec<u8>),
+    Events,
+    NotFound(String),
+    MethodNotAllowed,
+}
+
+fn json(value: &impl Serialize) -> serde_json::Result<Reply> {
+    Ok(Reply::Json(serde_json::to_vec(value)?))
+}
+
+fn route(progress: &Root, method: &Method, url: &str) -> serde_json::Result<Reply> {
+    if *method != Method::Get {
+        return Ok(Reply::MethodNotAllowed);
+    }
+    let path = url.split('?').next().unwrap_or_default();
+    let mut snapshot = Snapshot::default();
+    match path.trim_matches('/') {
+        "" => {
+            snapshot.update(progress);
+            Ok(Reply::Json(snapshot.to_json()?))
+        }
+        "tasks" => {
+            progress.sorted_snapshot(&mut snapshot.entries);
+            json(&tasks(&snapshot.entries))
+        }
+        "messages" => {
+            progress.copy_messages(&mut snapshot.messages);
+            json(&messages(&snapshot.messages))
+        }
+        "events" => Ok(Reply::Events),
+        _ => Ok(Reply::NotFound(format!("There is nothing at '{}'", path))),
+    }
+}
+
+fn error_body(error: String) -> Vec<u8> {
+    serde_json::to_vec(&ErrorResponse { error }).expect("serialization to always succeed")
+}
+
+/// Write a `snapshot` event into `out` whenever the `progress` tree changed, until `stop` is set or the client
+/// disconnects.
+fn stream_events(
+    progress: &Root,
+    out: &mut dyn Write,
+    interval: Duration,
+    stop: &AtomicBool,
+) -> io::Result<()> {
+    write!(
+        out,
+        "HTTP/1.1 200 OK\r\nContent-Type: text/event-stream\r\nCache-Control: no-cache\r\nConnection: close\r\n\r\n"
+    )?;
+    let mut snapshot = Snapshot::default();
+    let mut previous = Vec::new();
+    while !stop.load(Ordering::Relaxed) {
+        snapshot.update(progress);
+        let current = snapshot.to_json()?;
+        if current != previous {
+            out.write_all(b"event: snapshot\ndata: ")?;
+            out.write_all(&current)?;
+            out.write_all(b"\n\n")?;
+            previous = current;
+        } else {
+            out.write_all(b": unchanged\n\n")?;
+        }
+        out.flush()?;
+        thread::sleep(interval);
+    }
+    Ok(())
+}
+
+fn respond(
+    progress: &Root,
+    request: Request,
+    options: &Options,
+    stop: &Arc<AtomicBool>,
+) -> io::Result<Option<thread::JoinHandle<()>>> {
+    let (status, body) = match route(progress, request.method(), request.url()) {
+        Ok(Reply::Events) => {
+            let (progress, interval, stop) = (progress.clone(), options.interval, stop.clone());
+            let mut out = request.into_writer();
+            return Ok(Some(thread::spawn(move || {
+                // Clients disconnecting show up as errors, which is how event streams usually end.
+                stream_events(&progress, &mut out, interval, &stop).ok();
+            })));
+        }
+        Ok(Reply::Json(body)) => (200, body),
+        Ok(Reply::NotFound(msg)) => (404, error_body(msg)),
+        Ok(Reply::MethodNotAllowed) => (405, error_body("Only GET is supported".into())),
+        Err(err) => {
+            warn!("Failed to answer request for '{}': {}", request.url(), err);
+            (500, error_body(err.to_string()))
+        }
+    };
+    request
+        .respond(
+            Response::from_data(body)
+                .with_status_code(status)
+                .with_header(
+                    "Content-Type: application/json"
+                        .parse::<Header>()
+                        .expect("valid header"),
+                ),
+        )
+        .map(|_| None)
+}
+
+/// The handle to the thread of a server started with [`serve(…)`](./fn.serve.html).
+///
+/// Dropping it stops the server without waiting for it.
+pub struct JoinHandle {
+    addr: SocketAddr,
+    stop: Arc<AtomicBool>,
+    inner: Option<thread::JoinHandle<()>>,
+}
+
+impl JoinHandle {
+    /// The address the server is listening on, useful if it was bound to port 0.
+    pub fn addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    /// Stop accepting requests, end all event streams and wait for the server to stop.
+    ///
+    /// Event streams are waited for at most one `Options::interval`, as clients that stopped reading would block
+    /// them forever. These end once the client disconnects.
+    pub fn shutdown_and_wait(mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        if let Some(handle) = self.inner.take() {
+            handle
+                .join()
+                .unwrap_or_else(|panic| std::panic::resume_unwind(panic))
+        }
+    }
+}
+
+impl Drop for JoinHandle {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+    }
+}
+
+/// Serve the state of the `progress` tree via HTTP on `bind` in a thread, until the returned
+/// [`JoinHandle`](./struct.JoinHandle.html) is shut down or dropped.
+///
+/// Each client of the event stream is served by its own thread.
+pub fn serve(progress: Root, bind: impl ToSocketAddrs, options: Options) -> io::Result<JoinHandle> {
+    let server = Server::http(bind).map_err(io::Error::other)?;
+    let addr = server.server_addr();
+    let stop = Arc::new(AtomicBool::new(false));
+    let inner = thread::spawn({
+        let stop = stop.clone();
+        move || {
+            let mut streams = Vec::new();
+            while !stop.load(Ordering::Relaxed) {
+                let request = match server.recv_timeout(POLL_INTERVAL) {
+                    Ok(Some(request)) => request,
+                    Ok(None) => continue,
+                    Err(err) => {
+                        warn!("Failed to receive request: {}", err);
+                        continue;
+                    }
+                };
+                match respond(&progress, request, &options, &stop) {
+                    Ok(Some(stream)) => {
+                        streams.retain(|s: &thread::JoinHandle<()>| !s.is_finished());
+                        streams.push(stream)
+                    }
+                    Ok(None) => {}
+                    Err(err) => warn!("Failed to send response: {}", err),
+                }
+            }
+            // Streams notice `stop` within an interval, unless writing blocks as their client stopped reading.
+            // Those are left behind to end once the client disconnects.
+            let deadline = Instant::now() + options.interval + POLL_INTERVAL;
+            while streams.iter().any(|s| !s.is_finished()) && Instant::now() < deadline {
+                thread::sleep(POLL_INTERVAL / 10);
+            }
+        }
+    });
+    Ok(JoinHandle {
+        addr,
+        stop,
+        inner: Some(inner),
+    })
+}
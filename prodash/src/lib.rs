@@ -12,10 +12,43 @@ is indeed the case.
 
 The **terminal user interface** seems to be the least transparent part, but can be configured to refresh less frequently.
 
+# Reporting Progress
+
+Libraries can report progress through the [`Progress`](./trait.Progress.html) trait, which is implemented by
+`tree::Item` as well as by types to [log](./progress/struct.Log.html) or [discard](./progress/struct.Discard.html) progress,
+leaving it to the application to decide how it is displayed.
+
+# Forwarding Logs
+
+Records emitted with the `log` crate would be drawn over by the terminal user interface. A [`Logger`](./logger/index.html)
+turns them into messages of the progress tree instead, optionally appending them to a file as well.
+
 # Terminal User Interface
 
 By default, a TUI is provided to visualize all state. Have a look at [the example provided in the tui module](./tui/index.html).
 
+It draws with `termion` by default, which is available with the `tui-renderer-termion` feature. To use `crossterm` instead,
+for instance to support Windows, disable the default features and enable `tui-renderer` along with `tui-renderer-crossterm`.
+If both backends are enabled, `crossterm` is used.
+
+# Line Renderer
+
+When there is no terminal, like in CI or when output is piped, the [line renderer](./line/index.html) prints progress
+as plain lines of text instead. It is available with the `line-renderer` feature and doesn't depend on any terminal library.
+
+# Recording and Replay
+
+The state of a progress tree can be [recorded](./recording/index.html) into a file and replayed later, for instance
+to review a long-running session. It is available with the `recording` feature.
+
+On Unix, the progress tree of a running process can also be [shared](./recording/fn.share.html) on a socket, for other
+processes to attach to and display it, like the `prodash-attach` example does.
+
+# Remote Monitoring
+
+With the `http-exporter` feature, the progress tree can be [served](./http/index.html) as JSON via HTTP, along with a
+stream of Server-Sent Events, to watch it from a browser or to scrape it into dashboards.
+
 # A demo application
 
 Please have a look at the [dashboard demo](https://github.com/Byron/crates-io-cli-rs/blob/master/prodash/examples/dashboard.rs).
@@ -26,10 +59,31 @@ Run it with `cargo run --example dashboard` and see what else it can do by check
 
 */
 mod config;
+pub mod logger;
+pub mod progress;
 pub mod tree;
+pub mod unit;
 
 pub use config::TreeOptions;
+pub use progress::Progress;
 pub use tree::Root as Tree;
 
+#[cfg(all(
+    feature = "tui-renderer",
+    not(any(feature = "tui-renderer-termion", feature = "tui-renderer-crossterm"))
+))]
+compile_error!(
+    "The 'tui-renderer' needs a backend, please enable the 'tui-renderer-termion' or 'tui-renderer-crossterm' feature"
+);
+
 #[cfg(feature = "tui-renderer")]
 pub mod tui;
+
+#[cfg(feature = "line-renderer")]
+pub mod line;
+
+#[cfg(feature = "recording")]
+pub mod recording;
+
+#[cfg(feature = "http-exporter")]
+pub mod http;
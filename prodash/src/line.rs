@@ -0,0 +1,234 @@
+/*!
+* A module implementing a *non-interactive line renderer*, which periodically prints the state of a
+* [progress tree](../tree/struct.Root.html) as plain lines of text.
+*
+* It is useful whenever there is no terminal to draw into, like when running in CI or when output is piped
+* into a file, and doesn't depend on any terminal library.
+*
+* **Please note** that it is behind the `line-renderer` feature toggle, which is not enabled by default.
+*
+* # Example
+*
+* ```rust
+* # fn main() -> std::io::Result<()> {
+* use prodash::line;
+* let root = prodash::Tree::new();
+* let handle = line::render(
+*     std::io::stderr(),
+*     root.clone(),
+*     line::Options {
+*         throttle: std::time::Duration::from_millis(500),
+*         ..line::Options::default()
+*     },
+* );
+* let mut progress = root.add_child("task");
+* progress.init(Some(3), Some("items".into()));
+* for step in 1..=3 {
+*     progress.set(step);
+* }
+* progress.done("all items processed");
+* // print whatever changed since the last report, and stop.
+* handle.shutdown_and_wait()
+* # }
+* ```
+*/
+use crate::{
+    tree::{FieldsDisplay, Key, Message, Progress, ProgressState, Root, Value},
+    unit::UnitDisplay,
+};
+use std::{
+    collections::HashMap,
+    io,
+    sync::mpsc,
+    thread,
+    time::{Duration, SystemTime},
+};
+
+/// Configure the line renderer
+#[derive(Clone, Debug)]
+pub struct Options {
+    /// The minimum amount of time between two reports. Tasks that didn't change since the previous report aren't printed again.
+    pub throttle: Duration,
+    /// If true, organizational items without progress, like groups, will be printed as well.
+    pub show_groups: bool,
+    /// If true, a timestamp is printed in front of each line.
+    pub timestamp: bool,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            throttle: Duration::from_secs(1),
+            show_groups: false,
+            timestamp: true,
+        }
+    }
+}
+
+/// A handle to the thread printing progress, as returned by [`render(…)`](./fn.render.html).
+///
+/// Dropping it stops the renderer without printing a final report, and waits for it to stop.
+pub struct JoinHandle {
+    inner: Option<thread::JoinHandle<io::Result<()>>>,
+    stop: Option<mpsc::Sender<()>>,
+}
+
+impl JoinHandle {
+    /// Print one last report containing everything that changed since the previous one, and wait for
+    /// the renderer to stop.
+    pub fn shutdown_and_wait(mut self) -> io::Result<()> {
+        self.stop.take();
+        match self.inner.take() {
+            Some(handle) => handle
+                .join()
+                .unwrap_or_else(|panic| std::panic::resume_unwind(panic)),
+            None => Ok(()),
+        }
+    }
+}
+
+impl Drop for JoinHandle {
+    fn drop(&mut self) {
+        if let Some(stop) = self.stop.take() {
+            stop.send(()).ok();
+        }
+        if let Some(handle) = self.inner.take() {
+            handle.join().ok();
+        }
+    }
+}
+
+struct State {
+    options: Options,
+    entries: Vec<(Key, Value)>,
+    messages: Vec<Message>,
+    previous: HashMap<Key, Value>,
+    /// The amount of messages sent so far, to print only new ones.
+    messages_seen: usize,
+}
+
+impl State {
+    fn report(&mut self, progress: &Root, out: &mut impl io::Write) -> io::Result<()> {
+        self.messages_seen = progress.copy_new_messages(&mut self.messages, self.messages_seen);
+        for message in &self.messages {
+            self.write_timestamp(out, message.time)?;
+            write!(
+                out,
+                "{} {}: {}",
+                message.level.short_name(),
+                message.origin,
+                message.message
+            )?;
+            if !message.fields.is_empty() {
+                write!(out, " {}", FieldsDisplay(&message.fields))?;
+            }
+            writeln!(out)?;
+        }
+
+        progress.sorted_snapshot(&mut self.entries);
+        let now = SystemTime::now();
+        for (key, value) in &self.entries {
+            if value.progress.is_none() && !self.options.show_groups {
+                continue;
+            }
+            if matches!(self.previous.get(key), Some(previous) if previous.name == value.name
+                    && previous.outcome == value.outcome
+                    && without_throughput(&previous.progress) == without_throughput(&value.progress))
+            {
+                continue;
+            }
+            self.write_timestamp(out, now)?;
+            write!(
+                out,
+                "{:indent$}{}",
+                "",
+                value.name,
+                indent = key.level().saturating_sub(1) * 2
+            )?;
+            if let Some(progress) = &value.progress {
+                write!(out, " ")?;
+                write_progress(out, progress, now)?;
+            }
+            if let Some(outcome) = value.outcome {
+                write!(out, " ({})", outcome)?;
+            }
+            writeln!(out)?;
+        }
+        self.previous.clear();
+        self.previous.extend(self.entries.drain(..));
+        out.flush()
+    }
+
+    fn write_timestamp(&self, out: &mut impl io::Write, time: SystemTime) -> io::Result<()> {
+        if self.options.timestamp {
+            write!(out, "{} ", humantime::format_rfc3339_seconds(time))?;
+        }
+        Ok(())
+    }
+}
+
+/// Throughput changes as time passes, and shouldn't make a task seem changed.
+fn without_throughput(progress: &Option<Progress>) -> Option<Progress> {
+    progress.as_ref().map(|p| Progress {
+        throughput: None,
+        ..p.clone()
+    })
+}
+
+fn write_progress(out: &mut impl io::Write, p: &Progress, now: SystemTime) -> io::Result<()> {
+    write!(
+        out,
+        "{}",
+        UnitDisplay::new(p.unit.as_ref(), p.step, p.done_at)
+    )?;
+    if let Some(fraction) = p.fraction() {
+        write!(out, " ({:.0}%)", fraction * 100.0)?;
+    }
+    if let ProgressState::Blocked(eta) = p.state {
+        write!(out, " [blocked")?;
+        if let Some(eta) = eta.and_then(|eta| eta.duration_since(now).ok()) {
+            write!(
+                out,
+                " for {}",
+                humantime::format_duration(Duration::from_secs(eta.as_secs()))
+            )?;
+        }
+        write!(out, "]")?;
+    }
+    Ok(())
+}
+
+/// Print the state of the `progress` tree into `out` once per `options.throttle` in a thread, until the returned
+/// [`JoinHandle`](./struct.JoinHandle.html) is shut down or dropped.
+///
+/// New messages are printed first, followed by one line for each task whose progress changed since the last report.
+pub fn render(
+    out: impl io::Write + Send + 'static,
+    progress: Root,
+    options: Options,
+) -> JoinHandle {
+    let (stop, stop_receive) = mpsc::channel::<()>();
+    let inner = thread::spawn(move || -> io::Result<()> {
+        let mut out = out;
+        let mut state = State {
+            entries: Vec::with_capacity(progress.num_tasks()),
+            messages: Vec::with_capacity(progress.messages_capacity()),
+            previous: HashMap::new(),
+            messages_seen: 0,
+            options,
+        };
+        loop {
+            match stop_receive.recv_timeout(state.options.throttle) {
+                Err(mpsc::RecvTimeoutError::Timeout) => state.report(&progress, &mut out)?,
+                Err(mpsc::RecvTimeoutError::Disconnected) => {
+                    return state.report(&progress, &mut out)
+                }
+                Ok(()) => return Ok(()),
+            }
+        }
+    });
+    JoinHandle {
+        inner: Some(inner),
+        stop: Some(stop),
+    }
+}
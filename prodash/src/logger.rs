@@ -0,0 +1,136 @@
+//! A [`log::Log`](https://docs.rs/log/0.4/log/trait.Log.html) implementation which turns log records into
+//! [messages](../tree/struct.Message.html) of a progress tree.
+//!
+//! This way, everything logged with the `log` crate shows up in the messages pane of the terminal user interface
+//! instead of being written to the terminal it draws on.
+//!
+//! ```rust
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! use prodash::logger::{self, Logger};
+//! let root = prodash::Tree::new();
+//! Logger::new(&root, logger::Options::default())?.install()?;
+//!
+//! log::info!("this is a message");
+//! log::debug!("this is ignored by default");
+//!
+//! let mut messages = Vec::new();
+//! root.copy_messages(&mut messages);
+//! assert_eq!(messages.len(), 1);
+//! assert_eq!(messages[0].message, "this is a message");
+//! # Ok(())
+//! # }
+//! ```
+use crate::tree::{Message, MessageLevel, MessageRingBuffer, Root};
+use log::{Level, LevelFilter, Metadata, Record};
+use parking_lot::Mutex;
+use std::{
+    fs,
+    io::{self, Write},
+    path::PathBuf,
+    sync::Arc,
+    time::SystemTime,
+};
+
+/// Configure a [`Logger`](./struct.Logger.html).
+#[derive(Clone, Debug)]
+pub struct Options {
+    /// Records less severe than this are ignored.
+    pub level: LevelFilter,
+    /// If set, all messages are also appended to this file, one per line, so they are kept once the
+    /// messages are overwritten in the ring buffer.
+    pub pass_through: Option<PathBuf>,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            level: LevelFilter::Info,
+            pass_through: None,
+        }
+    }
+}
+
+/// A logger which pushes records into the messages of a progress tree.
+///
+/// Records with the `error` level become failures, and those with the `trace` level debug messages, while all other
+/// levels map to the message level of the same name. Their target, which usually is the module path, is used as origin.
+pub struct Logger {
+    messages: Arc<Mutex<MessageRingBuffer>>,
+    level: LevelFilter,
+    pass_through: Option<Mutex<fs::File>>,
+}
+
+impl Logger {
+    /// Create a new instance pushing messages into the ring buffer of `root`.
+    ///
+    /// Fails if the file to pass messages through to can't be opened.
+    pub fn new(
+        root: &Root,
+        Options {
+            level,
+            pass_through,
+        }: Options,
+    ) -> io::Result<Self> {
+        Ok(Logger {
+            messages: root.inner.lock().messages.clone(),
+            level,
+            pass_through: match pass_through {
+                Some(path) => Some(Mutex::new(
+                    fs::OpenOptions::new()
+                        .create(true)
+                        .append(true)
+                        .open(path)?,
+                )),
+                None => None,
+            },
+        })
+    }
+
+    /// Make this instance the global logger, and set the maximum level of the `log` crate accordingly.
+    ///
+    /// Fails if a global logger was already set.
+    pub fn install(self) -> Result<(), log::SetLoggerError> {
+        let level = self.level;
+        log::set_boxed_logger(Box::new(self))?;
+        log::set_max_level(level);
+        Ok(())
+    }
+}
+
+impl log::Log for Logger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= self.level
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+        let message = Message {
+            time: SystemTime::now(),
+            level: match record.level() {
+                Level::Error => MessageLevel::Failure,
+                Level::Warn => MessageLevel::Warning,
+                Level::Info => MessageLevel::Info,
+                Level::Debug | Level::Trace => MessageLevel::Debug,
+            },
+            origin: record.target().to_owned(),
+            message: record.args().to_string(),
+            fields: Vec::new(),
+        };
+        if let Some(out) = &self.pass_through {
+            // Lines are written unbuffered as the global logger is never dropped. There is no way to report errors,
+            // and the message still goes into the ring buffer.
+            out.lock()
+                .write_all(format!("{}\n", message).as_bytes())
+                .ok();
+        }
+        self.messages.lock().push_message(message);
+    }
+
+    fn flush(&self) {
+        if let Some(out) = &self.pass_through {
+            out.lock().flush().ok();
+        }
+    }
+}
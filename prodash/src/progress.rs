@@ -0,0 +1,224 @@
+//! A [`Progress`](./trait.Progress.html) trait for reporting progress without depending on a particular way of
+//! displaying it, along with implementations which discard or log it.
+//!
+//! Library code takes any `Progress` implementation, and leaves it to its callers to decide whether to show it in a
+//! [dashboard](../tree/struct.Item.html), [log](./struct.Log.html) or [discard](./struct.Discard.html) it.
+//!
+//! ```rust
+//! use prodash::Progress;
+//! fn count_files(mut progress: impl Progress, files: &[&str]) -> usize {
+//!     progress.init(Some(files.len() as u64), Some("files".into()));
+//!     for _ in files {
+//!         progress.inc();
+//!     }
+//!     progress.done("counted all files");
+//!     files.len()
+//! }
+//!
+//! let tree = prodash::Tree::new();
+//! count_files(tree.add_child("counting"), &["a", "b"]);
+//! count_files(prodash::progress::Log::new("counting"), &["a", "b"]);
+//! count_files(prodash::progress::Discard, &["a", "b"]);
+//! ```
+use crate::{
+    tree::{Item, MessageLevel, ProgressStep},
+    unit::{Unit, UnitDisplay},
+};
+use std::time::{Duration, Instant, SystemTime};
+
+/// A trait for reporting progress, with methods mirroring those of [`tree::Item`](../tree/struct.Item.html).
+pub trait Progress {
+    /// The type of progress returned by [`add_child(…)`](#tymethod.add_child).
+    type SubProgress: Progress;
+
+    /// Adds a new child, whose parent is this instance, with the given `name`.
+    fn add_child(&mut self, name: impl Into<String>) -> Self::SubProgress;
+
+    /// Initialize the progress with an optional upper bound `max` and an optional `unit`.
+    ///
+    /// See [`tree::Item::init(…)`](../tree/struct.Item.html#method.init) for details.
+    fn init(&mut self, max: Option<ProgressStep>, unit: Option<Unit>);
+
+    /// Set the current progress to the given `step`.
+    fn set(&mut self, step: ProgressStep);
+
+    /// Increment the current progress by the given `step`.
+    fn inc_by(&mut self, step: ProgressStep);
+
+    /// Increment the current progress by one.
+    fn inc(&mut self) {
+        self.inc_by(1)
+    }
+
+    /// Indicate that progress cannot be made, optionally until `eta`.
+    fn blocked(&mut self, eta: Option<SystemTime>);
+
+    /// Create a `message` of the given `level`.
+    fn message(&mut self, level: MessageLevel, message: impl AsRef<str>);
+
+    /// Create a message providing additional information about the progress thus far.
+    fn info(&mut self, message: impl AsRef<str>) {
+        self.message(MessageLevel::Info, message)
+    }
+
+    /// Create a message indicating the task is done
+    fn done(&mut self, message: impl AsRef<str>) {
+        self.message(MessageLevel::Success, message)
+    }
+
+    /// Create a message indicating the task failed
+    fn fail(&mut self, message: impl AsRef<str>) {
+        self.message(MessageLevel::Failure, message)
+    }
+
+    /// Create a message about a problem which doesn't keep the task from making progress.
+    fn warn(&mut self, message: impl AsRef<str>) {
+        self.message(MessageLevel::Warning, message)
+    }
+
+    /// Create a message with details which are only of interest when investigating an issue.
+    fn debug(&mut self, message: impl AsRef<str>) {
+        self.message(MessageLevel::Debug, message)
+    }
+}
+
+impl Progress for Item {
+    type SubProgress = Item;
+
+    fn add_child(&mut self, name: impl Into<String>) -> Self::SubProgress {
+        Item::add_child(self, name)
+    }
+
+    fn init(&mut self, max: Option<ProgressStep>, unit: Option<Unit>) {
+        Item::init(self, max, unit)
+    }
+
+    fn set(&mut self, step: ProgressStep) {
+        Item::set(self, step)
+    }
+
+    fn inc_by(&mut self, step: ProgressStep) {
+        Item::inc_by(self, step)
+    }
+
+    fn blocked(&mut self, eta: Option<SystemTime>) {
+        Item::blocked(self, eta)
+    }
+
+    fn message(&mut self, level: MessageLevel, message: impl AsRef<str>) {
+        Item::message(self, level, message)
+    }
+}
+
+/// A progress implementation which does nothing, for when progress isn't of interest.
+#[derive(Copy, Clone, Default, Debug)]
+pub struct Discard;
+
+impl Progress for Discard {
+    type SubProgress = Discard;
+
+    fn add_child(&mut self, _name: impl Into<String>) -> Self::SubProgress {
+        Discard
+    }
+
+    fn init(&mut self, _max: Option<ProgressStep>, _unit: Option<Unit>) {}
+
+    fn set(&mut self, _step: ProgressStep) {}
+
+    fn inc_by(&mut self, _step: ProgressStep) {}
+
+    fn blocked(&mut self, _eta: Option<SystemTime>) {}
+
+    fn message(&mut self, _level: MessageLevel, _message: impl AsRef<str>) {}
+}
+
+/// The minimal amount of time between two log lines about the progress of the same task.
+const LOG_INTERVAL: Duration = Duration::from_secs(1);
+
+/// A progress implementation which emits records using the `log` crate.
+///
+/// Messages are logged as they come, with failures using the `warn` level, while changes to the progress
+/// are logged at most once per second.
+#[derive(Clone, Debug)]
+pub struct Log {
+    name: String,
+    max: Option<ProgressStep>,
+    unit: Option<Unit>,
+    step: ProgressStep,
+    last_logged_at: Option<Instant>,
+}
+
+impl Log {
+    /// Create a new instance which logs the progress of the task with the given `name`.
+    pub fn new(name: impl Into<String>) -> Self {
+        Log {
+            name: name.into(),
+            max: None,
+            unit: None,
+            step: 0,
+            last_logged_at: None,
+        }
+    }
+
+    fn log_progress(&mut self) {
+        let now = Instant::now();
+        let is_done = matches!(self.max, Some(max) if self.step >= max);
+        if matches!(self.last_logged_at, Some(time) if now.duration_since(time) < LOG_INTERVAL)
+            && !is_done
+        {
+            return;
+        }
+        self.last_logged_at = Some(now);
+        log::info!(
+            "{}: {}",
+            self.name,
+            UnitDisplay::new(self.unit.as_ref(), self.step, self.max)
+        );
+    }
+}
+
+impl Progress for Log {
+    type SubProgress = Log;
+
+    fn add_child(&mut self, name: impl Into<String>) -> Self::SubProgress {
+        Log::new(format!("{}::{}", self.name, name.into()))
+    }
+
+    fn init(&mut self, max: Option<ProgressStep>, unit: Option<Unit>) {
+        self.max = max;
+        self.unit = unit;
+    }
+
+    fn set(&mut self, step: ProgressStep) {
+        self.step = step;
+        self.log_progress();
+    }
+
+    fn inc_by(&mut self, step: ProgressStep) {
+        self.step = self.step.saturating_add(step);
+        self.log_progress();
+    }
+
+    fn blocked(&mut self, eta: Option<SystemTime>) {
+        match eta.and_then(|eta| eta.duration_since(SystemTime::now()).ok()) {
+            Some(duration) => log::info!(
+                "{}: blocked for {}",
+                self.name,
+                humantime::format_duration(Duration::from_secs(duration.as_secs()))
+            ),
+            None => log::info!("{}: blocked", self.name),
+        }
+    }
+
+    fn message(&mut self, level: MessageLevel, message: impl AsRef<str>) {
+        match level {
+            MessageLevel::Debug => log::debug!("{}: {}", self.name, message.as_ref()),
+            MessageLevel::Warning | MessageLevel::Failure => {
+                log::warn!("{}: {}", self.name, message.as_ref())
+            }
+            MessageLevel::Info | MessageLevel::Success => {
+                log::info!("{}: {}", self.name, message.as_ref())
+            }
+        }
+    }
+}
@@ -0,0 +1,900 @@
+/*!
+* A module to *record* the state of a [progress tree](../tree/struct.Root.html) over time into a file, and to *replay*
+* it later, for instance to review how progress developed during a long-running session.
+*
+* Recordings are [JSON lines](http://jsonlines.org): a header followed by one frame per line, each containing the tasks
+* and messages that changed since the previous frame, along with the time at which it was taken.
+*
+* Recordings can also be [followed](./fn.follow.html) while they are written. On Unix, this is used to [share](./fn.share.html)
+* the progress tree of a running process on a socket, to [attach](./fn.attach.html) to it from another one at any time.
+*
+* **Please note** that it is behind the `recording` feature toggle, which is not enabled by default.
+* Replaying in the terminal user interface additionally needs the `tui-renderer` feature.
+*
+* # Example
+*
+* ```rust
+* # fn main() -> std::io::Result<()> {
+* use prodash::recording::{self, Player, Recording};
+* let root = prodash::Tree::new();
+* let handle = recording::record(Vec::new(), root.clone(), recording::Options::default());
+* let mut progress = root.add_child("task");
+* progress.init(Some(3), Some("items".into()));
+* progress.set(2);
+* progress.done("almost there");
+* // record one last frame, and obtain what was written.
+* let recorded = handle.shutdown_and_wait()?;
+*
+* let mut player = Player::new(Recording::read_from(recorded.as_slice())?);
+* player.seek(player.duration());
+* let mut entries = Vec::new();
+* player.root().sorted_snapshot(&mut entries);
+* assert_eq!(entries[0].1.progress.as_ref().map(|p| p.step), Some(2));
+* # Ok(())
+* # }
+* ```
+*/
+use crate::{
+    tree::{
+        Key, Message, MessageLevel, Outcome, Progress, ProgressState, ProgressStep, Root,
+        Throughput, Value,
+    },
+    unit::{Scale, Unit},
+    TreeOptions,
+};
+use serde_derive::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, HashMap, VecDeque},
+    io,
+    sync::mpsc,
+    thread,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+const FORMAT_VERSION: u32 = 1;
+
+fn to_millis(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64
+}
+
+fn from_millis(millis: u64) -> SystemTime {
+    UNIX_EPOCH + Duration::from_millis(millis)
+}
+
+#[derive(Serialize, Deserialize)]
+struct Header {
+    version: u32,
+    messages_capacity: usize,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+enum RecordedUnit {
+    Label(String),
+    BinaryBytes,
+    DecimalBytes,
+    Duration,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+struct RecordedThroughput {
+    steps: ProgressStep,
+    timespan_ms: u64,
+    eta_ms: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+struct RecordedProgress {
+    step: ProgressStep,
+    done_at: Option<ProgressStep>,
+    unit: Option<RecordedUnit>,
+    /// If set, the task was blocked, optionally until the given time.
+    blocked: Option<Option<u64>>,
+    throughput: Option<RecordedThroughput>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+struct Task {
+    key: Vec<u64>,
+    name: String,
+    progress: Option<RecordedProgress>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    outcome: Option<RecordedOutcome>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+enum RecordedOutcome {
+    Succeeded,
+    Failed,
+    Cancelled,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug)]
+enum RecordedLevel {
+    Debug,
+    Info,
+    Warning,
+    Failure,
+    Success,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+struct RecordedMessage {
+    time_ms: u64,
+    level: RecordedLevel,
+    origin: String,
+    message: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    fields: Vec<(String, String)>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+struct Frame {
+    time_ms: u64,
+    tasks: Vec<Task>,
+    removed: Vec<Vec<u64>>,
+    messages: Vec<RecordedMessage>,
+}
+
+impl Task {
+    fn new(key: &Key, value: &Value) -> Self {
+        Task {
+            key: key.ids().to_vec(),
+            name: value.name.clone(),
+            progress: value.progress.as_ref().map(|p| RecordedProgress {
+                step: p.step,
+                done_at: p.done_at,
+                unit: p.unit.as_ref().map(|unit| match unit {
+                    Unit::Label(label) => RecordedUnit::Label(label.to_string()),
+                    Unit::Bytes(Scale::Binary) => RecordedUnit::BinaryBytes,
+                    Unit::Bytes(Scale::Decimal) => RecordedUnit::DecimalBytes,
+                    Unit::Duration => RecordedUnit::Duration,
+                    Unit::Custom(display) => {
+                        let mut label = String::new();
+                        display.display_unit(&mut label, p.step).ok();
+                        RecordedUnit::Label(label.trim_start().to_owned())
+                    }
+                }),
+                blocked: match p.state {
+                    ProgressState::Blocked(eta) => Some(eta.map(to_millis)),
+                    ProgressState::Running => None,
+                },
+                throughput: p
+                    .throughput
+                    .filter(|t| t.steps > 0)
+                    .map(|t| RecordedThroughput {
+                        steps: t.steps,
+                        timespan_ms: t.timespan.as_millis() as u64,
+                        eta_ms: t.eta.map(to_millis),
+                    }),
+            }),
+            outcome: value.outcome.map(|outcome| match outcome {
+                Outcome::Succeeded => RecordedOutcome::Succeeded,
+                Outcome::Failed => RecordedOutcome::Failed,
+                Outcome::Cancelled => RecordedOutcome::Cancelled,
+            }),
+        }
+    }
+
+    /// Returns the value as it would be at the recorded time `position_ms`, observed now.
+    fn to_value(&self, position_ms: u64, now: SystemTime) -> Value {
+        let time_from_now = |time_ms: u64| {
+            if time_ms >= position_ms {
+                now + Duration::from_millis(time_ms - position_ms)
+            } else {
+                now.checked_sub(Duration::from_millis(position_ms - time_ms))
+                    .unwrap_or(now)
+            }
+        };
+        Value {
+            name: self.name.clone(),
+            progress: self.progress.as_ref().map(|p| Progress {
+                step: p.step,
+                done_at: p.done_at,
+                unit: p.unit.as_ref().map(|unit| match unit {
+                    RecordedUnit::Label(label) => Unit::Label(label.clone().into()),
+                    RecordedUnit::BinaryBytes => Unit::Bytes(Scale::Binary),
+                    RecordedUnit::DecimalBytes => Unit::Bytes(Scale::Decimal),
+                    RecordedUnit::Duration => Unit::Duration,
+                }),
+                state: match p.blocked {
+                    Some(eta) => ProgressState::Blocked(eta.map(time_from_now)),
+                    None => ProgressState::Running,
+                },
+                throughput: p.throughput.as_ref().map(|t| Throughput {
+                    steps: t.steps,
+                    timespan: Duration::from_millis(t.timespan_ms),
+                    eta: t.eta_ms.map(time_from_now),
+                }),
+            }),
+            outcome: self.outcome.map(|outcome| match outcome {
+                RecordedOutcome::Succeeded => Outcome::Succeeded,
+                RecordedOutcome::Failed => Outcome::Failed,
+                RecordedOutcome::Cancelled => Outcome::Cancelled,
+            }),
+        }
+    }
+}
+
+impl From<&Message> for RecordedMessage {
+    fn from(m: &Message) -> Self {
+        RecordedMessage {
+            time_ms: to_millis(m.time),
+            level: match m.level {
+                MessageLevel::Debug => RecordedLevel::Debug,
+                MessageLevel::Info => RecordedLevel::Info,
+                MessageLevel::Warning => RecordedLevel::Warning,
+                MessageLevel::Failure => RecordedLevel::Failure,
+                MessageLevel::Success => RecordedLevel::Success,
+            },
+            origin: m.origin.clone(),
+            message: m.message.clone(),
+            fields: m.fields.clone(),
+        }
+    }
+}
+
+impl From<&RecordedMessage> for Message {
+    fn from(m: &RecordedMessage) -> Self {
+        Message {
+            time: from_millis(m.time_ms),
+            level: match m.level {
+                RecordedLevel::Debug => MessageLevel::Debug,
+                RecordedLevel::Info => MessageLevel::Info,
+                RecordedLevel::Warning => MessageLevel::Warning,
+                RecordedLevel::Failure => MessageLevel::Failure,
+                RecordedLevel::Success => MessageLevel::Success,
+            },
+            origin: m.origin.clone(),
+            message: m.message.clone(),
+            fields: m.fields.clone(),
+        }
+    }
+}
+
+fn write_line(out: &mut impl io::Write, value: &impl serde::Serialize) -> io::Result<()> {
+    serde_json::to_writer(&mut *out, value)?;
+    writeln!(out)
+}
+
+/// Configure the recorder
+#[derive(Clone, Debug)]
+pub struct Options {
+    /// The amount of time between two frames. Frames are only written if something changed.
+    pub interval: Duration,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            interval: Duration::from_secs(1),
+        }
+    }
+}
+
+/// A handle to the thread writing the recording, as returned by [`record(…)`](./fn.record.html).
+///
+/// Dropping it stops the recorder without recording a final frame.
+pub struct JoinHandle<W> {
+    inner: Option<thread::JoinHandle<io::Result<W>>>,
+    stop: Option<mpsc::Sender<()>>,
+}
+
+impl<W> JoinHandle<W> {
+    /// Record one last frame, marking the end of the recording, and wait for the recorder to stop.
+    ///
+    /// Returns the writer passed to [`record(…)`](./fn.record.html).
+    pub fn shutdown_and_wait(mut self) -> io::Result<W> {
+        self.stop.take();
+        self.inner
+            .take()
+            .expect("handle to be present until shutdown")
+            .join()
+            .unwrap_or_else(|panic| std::panic::resume_unwind(panic))
+    }
+
+    fn is_finished(&self) -> bool {
+        !matches!(&self.inner, Some(handle) if !handle.is_finished())
+    }
+}
+
+impl<W> Drop for JoinHandle<W> {
+    fn drop(&mut self) {
+        if let Some(stop) = self.stop.take() {
+            stop.send(()).ok();
+        }
+    }
+}
+
+struct Recorder {
+    entries: Vec<(Key, Value)>,
+    messages: Vec<Message>,
+    previous: HashMap<Vec<u64>, Task>,
+    /// The amount of messages sent so far, to record only new ones.
+    messages_seen: usize,
+}
+
+impl Recorder {
+    fn record_frame(
+        &mut self,
+        progress: &Root,
+        out: &mut impl io::Write,
+        force: bool,
+    ) -> io::Result<()> {
+        let mut frame = Frame {
+            time_ms: to_millis(SystemTime::now()),
+            ..Frame::default()
+        };
+        self.messages_seen = progress.copy_new_messages(&mut self.messages, self.messages_seen);
+        frame
+            .messages
+            .extend(self.messages.iter().map(RecordedMessage::from));
+
+        progress.sorted_snapshot(&mut self.entries);
+        let mut current = HashMap::with_capacity(self.entries.len());
+        for (key, value) in self.entries.drain(..) {
+            let task = Task::new(&key, &value);
+            match self.previous.remove(&task.key) {
+                Some(previous) if previous == task => {}
+                _ => frame.tasks.push(task.clone()),
+            }
+            current.insert(task.key.clone(), task);
+        }
+        frame
+            .removed
+            .extend(self.previous.drain().map(|(key, _)| key));
+        self.previous = current;
+
+        if force
+            || !(frame.tasks.is_empty() && frame.removed.is_empty() && frame.messages.is_empty())
+        {
+            write_line(out, &frame)?;
+        }
+        out.flush()
+    }
+}
+
+/// Record the state of the `progress` tree into `out` once per `options.interval` in a thread, until the returned
+/// [`JoinHandle`](./struct.JoinHandle.html) is shut down or dropped.
+///
+/// Recordings can be read with [`Recording::read_from(…)`](./struct.Recording.html#method.read_from).
+///
+/// [Custom units](../unit/enum.Unit.html#variant.Custom) can't be recorded. Instead, the unit they display for the
+/// current value is recorded as a label, and values are replayed as plain numbers.
+pub fn record<W>(out: W, progress: Root, options: Options) -> JoinHandle<W>
+where
+    W: io::Write + Send + 'static,
+{
+    let (stop, stop_receive) = mpsc::channel::<()>();
+    let inner = thread::spawn(move || -> io::Result<W> {
+        let mut out = out;
+        write_line(
+            &mut out,
+            &Header {
+                version: FORMAT_VERSION,
+                messages_capacity: progress.messages_capacity(),
+            },
+        )?;
+        let mut recorder = Recorder {
+            entries: Vec::with_capacity(progress.num_tasks()),
+            messages: Vec::with_capacity(progress.messages_capacity()),
+            previous: HashMap::new(),
+            messages_seen: 0,
+        };
+        recorder.record_frame(&progress, &mut out, true)?;
+        loop {
+            match stop_receive.recv_timeout(options.interval) {
+                Err(mpsc::RecvTimeoutError::Timeout) => {
+                    recorder.record_frame(&progress, &mut out, false)?
+                }
+                Err(mpsc::RecvTimeoutError::Disconnected) => {
+                    recorder.record_frame(&progress, &mut out, true)?;
+                    return Ok(out);
+                }
+                Ok(()) => return Ok(out),
+            }
+        }
+    });
+    JoinHandle {
+        inner: Some(inner),
+        stop: Some(stop),
+    }
+}
+
+/// A recording of a progress tree, as written by [`record(…)`](./fn.record.html).
+#[derive(Clone, Debug)]
+pub struct Recording {
+    messages_capacity: usize,
+    frames: Vec<Frame>,
+}
+
+fn invalid_data(err: impl Into<Box<dyn std::error::Error + Send + Sync>>) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, err)
+}
+
+fn read_header(lines: &mut impl Iterator<Item = io::Result<String>>) -> io::Result<Header> {
+    let header: Header = match lines.next() {
+        Some(line) => serde_json::from_str(&line?).map_err(invalid_data)?,
+        None => return Err(invalid_data("The recording is empty")),
+    };
+    if header.version != FORMAT_VERSION {
+        return Err(invalid_data(format!(
+            "Cannot read recordings of version {}, only version {} is supported",
+            header.version, FORMAT_VERSION
+        )));
+    }
+    Ok(header)
+}
+
+impl Recording {
+    /// Read a recording from `input`.
+    pub fn read_from(input: impl io::BufRead) -> io::Result<Recording> {
+        let mut lines = input.lines();
+        let header = read_header(&mut lines)?;
+        let frames = lines
+            .map(|line| serde_json::from_str(&line?).map_err(invalid_data))
+            .collect::<io::Result<Vec<Frame>>>()?;
+        Ok(Recording {
+            messages_capacity: header.messages_capacity,
+            frames,
+        })
+    }
+
+    /// Returns the amount of time between the first frame and the latest one.
+    ///
+    /// Frames are recorded with the system time, which may step back, so the latest frame isn't necessarily the last.
+    pub fn duration(&self) -> Duration {
+        match (
+            self.frames.first(),
+            self.frames.iter().map(|f| f.time_ms).max(),
+        ) {
+            (Some(first), Some(latest_ms)) => {
+                Duration::from_millis(latest_ms.saturating_sub(first.time_ms))
+            }
+            _ => Duration::default(),
+        }
+    }
+}
+
+/// The tasks and messages of a recording as of the last frame applied to it.
+struct Replica {
+    root: Root,
+    messages_capacity: usize,
+    tasks: BTreeMap<Vec<u64>, Task>,
+    messages: VecDeque<RecordedMessage>,
+}
+
+impl Replica {
+    fn new(messages_capacity: usize) -> Self {
+        let mut root: Root = TreeOptions {
+            message_buffer_capacity: messages_capacity,
+            ..TreeOptions::default()
+        }
+        .into();
+        // the recording contains the throughput, which would otherwise depend on when the frames are applied.
+        root.throughput = None;
+        Replica {
+            root,
+            messages_capacity,
+            tasks: BTreeMap::new(),
+            messages: VecDeque::new(),
+        }
+    }
+
+    fn clear(&mut self) {
+        self.tasks.clear();
+        self.messages.clear();
+    }
+
+    fn apply(&mut self, frame: &Frame) {
+        for key in &frame.removed {
+            self.tasks.remove(key);
+        }
+        for task in &frame.tasks {
+            self.tasks.insert(task.key.clone(), task.clone());
+        }
+        self.messages.extend(frame.messages.iter().cloned());
+        while self.messages.len() > self.messages_capacity {
+            self.messages.pop_front();
+        }
+    }
+
+    /// Make the tree show the tasks and messages as they were at the recorded time `position_ms`.
+    fn update_root(&self, position_ms: u64) {
+        let now = SystemTime::now();
+        let inner = self.root.inner.lock();
+        inner.tree.clear();
+        for task in self.tasks.values() {
+            inner
+                .tree
+                .insert(Key::from_ids(&task.key), task.to_value(position_ms, now));
+        }
+        let mut messages = inner.messages.lock();
+        messages.clear();
+        for message in &self.messages {
+            messages.push_message(message.into());
+        }
+    }
+}
+
+/// Replays a [`Recording`](./struct.Recording.html) by updating a [`Root`](../tree/struct.Root.html) to the state
+/// at the current position.
+///
+/// The position only changes when calling [`seek(…)`](#method.seek) or [`advance(…)`](#method.advance), which makes
+/// replays deterministic.
+pub struct Player {
+    recording: Recording,
+    replica: Replica,
+    next_frame: usize,
+    position: Duration,
+    speed: f32,
+    paused: bool,
+}
+
+impl Player {
+    /// Create a new player, positioned at the beginning of the `recording`.
+    pub fn new(recording: Recording) -> Self {
+        let mut player = Player {
+            replica: Replica::new(recording.messages_capacity),
+            recording,
+            next_frame: 0,
+            position: Duration::default(),
+            speed: 1.0,
+            paused: false,
+        };
+        player.seek(Duration::default());
+        player
+    }
+
+    /// Returns the tree showing the state of the recording at the current position.
+    ///
+    /// Use it with any renderer, or with [`replay(…)`](./fn.replay.html) to control the player interactively.
+    pub fn root(&self) -> Root {
+        self.replica.root.clone()
+    }
+
+    /// Returns the duration of the recording.
+    pub fn duration(&self) -> Duration {
+        self.recording.duration()
+    }
+
+    /// Returns the current position, with `Duration::default()` being the beginning of the recording.
+    pub fn position(&self) -> Duration {
+        self.position
+    }
+
+    /// Returns the factor by which the replay is faster than the recording.
+    pub fn speed(&self) -> f32 {
+        self.speed
+    }
+
+    /// Set the factor by which the replay is faster than the recording, like `2.0` to play it twice as fast.
+    pub fn set_speed(&mut self, speed: f32) {
+        self.speed = speed.max(0.0);
+    }
+
+    /// Returns true if [`advance(…)`](#method.advance) doesn't change the position.
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    /// Pause or resume the replay.
+    pub fn set_paused(&mut self, paused: bool) {
+        self.paused = paused;
+    }
+
+    /// Move the position forward by the given amount of `elapsed` time, multiplied by the speed, unless paused.
+    pub fn advance(&mut self, elapsed: Duration) {
+        if !self.paused {
+            self.seek(self.position + elapsed.mul_f32(self.speed));
+        }
+    }
+
+    /// Change the position to the given one, which is clamped to the duration of the recording, and update the tree.
+    pub fn seek(&mut self, position: Duration) {
+        let position = position.min(self.duration());
+        if position < self.position {
+            self.replica.clear();
+            self.next_frame = 0;
+        }
+        self.position = position;
+
+        let start_ms = match self.recording.frames.first() {
+            Some(frame) => frame.time_ms,
+            None => return,
+        };
+        let position_ms = start_ms + position.as_millis() as u64;
+        for frame in self
+            .recording
+            .frames
+            .iter()
+            .skip(self.next_frame)
+            .take_while(|f| f.time_ms <= position_ms)
+        {
+            self.replica.apply(frame);
+            self.next_frame += 1;
+        }
+        self.replica.update_root(position_ms);
+    }
+}
+
+/// Follows a recording while it is being written, as returned by [`follow(…)`](./fn.follow.html).
+pub struct Follower {
+    root: Root,
+    inner: Option<thread::JoinHandle<io::Result<()>>>,
+    #[cfg(unix)]
+    connection: Option<std::os::unix::net::UnixStream>,
+}
+
+impl Follower {
+    /// Returns the tree showing the state of the last frame read.
+    pub fn root(&self) -> Root {
+        self.root.clone()
+    }
+
+    /// Returns true until the end of the input was reached or it couldn't be read.
+    pub fn is_following(&self) -> bool {
+        matches!(&self.inner, Some(handle) if !handle.is_finished())
+    }
+
+    /// Wait for the end of the input, returning an error if it couldn't be read.
+    pub fn wait(mut self) -> io::Result<()> {
+        self.inner
+            .take()
+            .expect("handle to be present until waited for")
+            .join()
+            .unwrap_or_else(|panic| std::panic::resume_unwind(panic))
+    }
+}
+
+/// Closes the connection of followers created by [`attach(…)`](./fn.attach.html) and waits for their thread to stop.
+///
+/// Other followers can't interrupt reading their input, so their thread keeps running until it ends.
+impl Drop for Follower {
+    fn drop(&mut self) {
+        #[cfg(unix)]
+        if let Some(connection) = self.connection.take() {
+            connection.shutdown(std::net::Shutdown::Both).ok();
+            if let Some(handle) = self.inner.take() {
+                handle.join().ok();
+            }
+        }
+    }
+}
+
+/// Read the header of a recording from `input`, and apply each of its frames to the [root](./struct.Follower.html#method.root)
+/// of the returned follower as soon as it was written, in a thread.
+///
+/// This makes it possible to watch a recording while it is written by [`record(…)`](./fn.record.html), for instance
+/// through a pipe.
+pub fn follow(input: impl io::BufRead + Send + 'static) -> io::Result<Follower> {
+    let mut lines = input.lines();
+    let header = read_header(&mut lines)?;
+    let mut replica = Replica::new(header.messages_capacity);
+    let root = replica.root.clone();
+    let inner = thread::spawn(move || -> io::Result<()> {
+        for line in lines {
+            let frame: Frame = serde_json::from_str(&line?).map_err(invalid_data)?;
+            replica.apply(&frame);
+            replica.update_root(frame.time_ms);
+        }
+        Ok(())
+    });
+    Ok(Follower {
+        root,
+        inner: Some(inner),
+        #[cfg(unix)]
+        connection: None,
+    })
+}
+
+#[cfg(feature = "tui-renderer")]
+mod replay {
+    use super::Player;
+    use crate::tui::{self, draw, engine::Controller, Event, Key, TuiOptions};
+    use humantime::format_duration;
+    use std::time::{Duration, Instant};
+
+    const SEEK_STEP: Duration = Duration::from_secs(10);
+
+    struct Controls {
+        player: Player,
+        title: String,
+        last_draw: Option<Instant>,
+    }
+
+    impl Controller for Controls {
+        fn handle_key(&mut self, key: Key) -> bool {
+            let player = &mut self.player;
+            match key {
+                Key::Char(' ') => player.set_paused(!player.is_paused()),
+                Key::Left => {
+                    player.seek(player.position().checked_sub(SEEK_STEP).unwrap_or_default())
+                }
+                Key::Right => player.seek(player.position() + SEEK_STEP),
+                Key::Home => player.seek(Duration::default()),
+                Key::Char('+') => player.set_speed((player.speed() * 2.0).min(64.0)),
+                Key::Char('-') => player.set_speed((player.speed() / 2.0).max(1.0 / 64.0)),
+                _ => return false,
+            }
+            true
+        }
+
+        fn before_draw(&mut self, state: &mut draw::State) {
+            let now = Instant::now();
+            if let Some(last_draw) = self.last_draw {
+                self.player.advance(now.duration_since(last_draw));
+            }
+            self.last_draw = Some(now);
+            let player = &self.player;
+            state.title = format!(
+                "{} ─ {} {} / {} at {}x",
+                self.title,
+                if player.is_paused() {
+                    "paused"
+                } else {
+                    "playing"
+                },
+                format_duration(Duration::from_secs(player.position().as_secs())),
+                format_duration(Duration::from_secs(player.duration().as_secs())),
+                player.speed()
+            );
+        }
+    }
+
+    /// Returns a future that replays a recording with the given `player` in the terminal user interface, like
+    /// [`tui::render_with_input(…)`](../tui/fn.render_with_input.html) would.
+    ///
+    /// Besides the usual keys, the replay can be controlled with the following ones:
+    /// * `space` - pause or resume
+    /// * `←` and `→` - seek backward and forward by 10 seconds
+    /// * `home` - seek to the beginning
+    /// * `+` and `-` - double or halve the speed
+    pub fn replay(
+        player: Player,
+        options: TuiOptions,
+        events: impl futures::Stream<Item = Event> + Send,
+    ) -> Result<impl std::future::Future<Output = ()>, std::io::Error> {
+        let root = player.root();
+        let title = options.title.clone();
+        tui::engine::render_with_controller(
+            root,
+            options,
+            events,
+            Controls {
+                player,
+                title,
+                last_draw: None,
+            },
+        )
+    }
+}
+
+#[cfg(feature = "tui-renderer")]
+pub use replay::replay;
+
+#[cfg(unix)]
+mod share {
+    use super::{follow, record, Follower, JoinHandle, Options};
+    use crate::tree::Root;
+    use std::{
+        io::{self, BufReader},
+        os::unix::net::{UnixListener, UnixStream},
+        path::{Path, PathBuf},
+        sync::{
+            atomic::{AtomicBool, Ordering},
+            Arc,
+        },
+        thread,
+        time::Duration,
+    };
+
+    /// The longest time it takes to notice that sharing should stop.
+    const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+    /// The handle to the thread sharing a progress tree, as returned by [`share(…)`](./fn.share.html).
+    ///
+    /// Dropping it stops sharing without waiting for it.
+    pub struct ShareHandle {
+        stop: Arc<AtomicBool>,
+        inner: Option<thread::JoinHandle<io::Result<()>>>,
+    }
+
+    impl ShareHandle {
+        /// Stop accepting connections, send a final frame to all attached clients and disconnect them, remove the socket
+        /// and wait for all threads to stop.
+        pub fn shutdown_and_wait(mut self) -> io::Result<()> {
+            self.stop.store(true, Ordering::Relaxed);
+            self.inner
+                .take()
+                .expect("handle to be present until shutdown")
+                .join()
+                .unwrap_or_else(|panic| std::panic::resume_unwind(panic))
+        }
+    }
+
+    impl Drop for ShareHandle {
+        fn drop(&mut self) {
+            self.stop.store(true, Ordering::Relaxed);
+        }
+    }
+
+    fn accept(
+        listener: &UnixListener,
+        progress: &Root,
+        options: &Options,
+        stop: &AtomicBool,
+    ) -> io::Result<()> {
+        let mut recorders: Vec<JoinHandle<UnixStream>> = Vec::new();
+        let res = loop {
+            if stop.load(Ordering::Relaxed) {
+                break Ok(());
+            }
+            match listener.accept() {
+                Ok((connection, _)) => {
+                    if let Err(err) = connection.set_nonblocking(false) {
+                        break Err(err);
+                    }
+                    recorders.retain(|recorder| !recorder.is_finished());
+                    recorders.push(record(connection, progress.clone(), options.clone()));
+                }
+                Err(err) if err.kind() == io::ErrorKind::WouldBlock => thread::sleep(POLL_INTERVAL),
+                Err(err) => break Err(err),
+            }
+        };
+        for recorder in recorders {
+            // Recorders fail once their client detached, which is expected.
+            // Dropping the returned connection disconnects the client.
+            recorder.shutdown_and_wait().ok();
+        }
+        res
+    }
+
+    /// Listen on a Unix domain socket at `path` in a thread, and [record](./fn.record.html) the `progress` tree into
+    /// each connection with the given `options`, until the returned [`ShareHandle`](./struct.ShareHandle.html) is shut
+    /// down or dropped.
+    ///
+    /// Clients use [`attach(…)`](./fn.attach.html) to follow the recording. Each of them is served by its own thread,
+    /// which ends when the client detaches without affecting the `progress` tree or other clients. This relies on
+    /// `SIGPIPE` being ignored, as it is by default in Rust programs.
+    ///
+    /// Fails if `path` already exists. The socket is removed once sharing stops.
+    /// Custom units are shared as labels, as with `record(…)`.
+    pub fn share(
+        progress: Root,
+        path: impl AsRef<Path>,
+        options: Options,
+    ) -> io::Result<ShareHandle> {
+        let path: PathBuf = path.as_ref().into();
+        let listener = UnixListener::bind(&path)?;
+        listener.set_nonblocking(true)?;
+        let stop = Arc::new(AtomicBool::new(false));
+        let inner = thread::spawn({
+            let stop = stop.clone();
+            move || -> io::Result<()> {
+                let res = accept(&listener, &progress, &options, &stop);
+                std::fs::remove_file(&path).ok();
+                res
+            }
+        });
+        Ok(ShareHandle {
+            stop,
+            inner: Some(inner),
+        })
+    }
+
+    /// Connect to a progress tree [shared](./fn.share.html) on the Unix domain socket at `path` and
+    /// [follow](./fn.follow.html) it.
+    ///
+    /// Dropping the returned follower detaches from the shared tree.
+    pub fn attach(path: impl AsRef<Path>) -> io::Result<Follower> {
+        let connection = UnixStream::connect(path)?;
+        let mut follower = follow(BufReader::new(connection.try_clone()?))?;
+        follower.connection = Some(connection);
+        Ok(follower)
+    }
+}
+
+#[cfg(unix)]
+pub use share::{attach, share, ShareHandle};
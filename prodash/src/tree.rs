@@ -1,12 +1,20 @@
-use crate::TreeOptions;
+use crate::{unit::Unit, TreeOptions};
 use dashmap::DashMap;
 use parking_lot::Mutex;
-use std::{sync::Arc, time::SystemTime};
+use smallvec::SmallVec;
+use std::{
+    collections::{HashMap, VecDeque},
+    fmt,
+    sync::Arc,
+    time::{Duration, Instant, SystemTime},
+};
 
 /// The top-level of the progress tree.
 #[derive(Clone, Debug)]
 pub struct Root {
     pub(crate) inner: Arc<Mutex<Item>>,
+    /// If unset, throughput isn't computed, as when values are provided by a replay.
+    pub(crate) throughput: Option<Arc<Mutex<ThroughputTracker>>>,
 }
 
 impl Root {
@@ -39,16 +47,30 @@ impl Root {
 
     /// Copy the entire progress tree into the given `out` vector, so that
     /// it can be traversed from beginning to end in order of hierarchy.
+    ///
+    /// Each snapshot also samples the progress of all tasks to provide their [`Throughput`](./struct.Throughput.html),
+    /// which thus depends on snapshots being taken regularly, as done by all renderers. Snapshots of all callers are
+    /// sampled alike, see [`ThroughputTracker`](./struct.ThroughputTracker.html) for why that's fine.
+    ///
+    /// Finished tasks whose [retention](../struct.TreeOptions.html#structfield.retention) expired are removed first.
     pub fn sorted_snapshot(&self, out: &mut Vec<(Key, Value)>) {
         out.clear();
-        out.extend(
-            self.inner
-                .lock()
-                .tree
-                .iter()
-                .map(|r| (r.key().clone(), r.value().clone())),
-        );
-        out.sort_by_key(|t| t.0);
+        {
+            let inner = self.inner.lock();
+            if let Some(retention) = &inner.retention {
+                retention.remove_expired(&inner.tree);
+            }
+            out.extend(
+                inner
+                    .tree
+                    .iter()
+                    .map(|r| (r.key().clone(), r.value().clone())),
+            );
+        }
+        out.sort_by(|a, b| a.0.cmp(&b.0));
+        if let Some(throughput) = &self.throughput {
+            throughput.lock().update(out);
+        }
     }
 
     /// Copy all messages from the internal ring buffer into the given `out`
@@ -56,23 +78,60 @@ impl Root {
     pub fn copy_messages(&self, out: &mut Vec<Message>) {
         self.inner.lock().messages.lock().copy_into(out);
     }
+
+    /// Copy the messages sent since the last call into the given `out` vector, ordered from oldest to newest, skipping
+    /// those which were overwritten in the ring buffer in the meantime.
+    ///
+    /// `seen` is the amount of messages sent so far as returned by the previous call, or `0` for the first one.
+    /// Unlike comparing times, this works for messages sent at the same time as well.
+    pub fn copy_new_messages(&self, out: &mut Vec<Message>, seen: usize) -> usize {
+        let inner = self.inner.lock();
+        let messages = inner.messages.lock();
+        messages.copy_into(out);
+        let sent = messages.counts.total();
+        // the amount of messages sent only decreases if the buffer was cleared, as when replaying recordings.
+        let new = sent.checked_sub(seen).unwrap_or(sent).min(out.len());
+        out.drain(..out.len() - new);
+        sent
+    }
+
+    /// Returns the amount of messages sent of each level, including those which were overwritten in the ring buffer.
+    pub fn message_counts(&self) -> MessageCounts {
+        self.inner.lock().messages.lock().counts
+    }
 }
 
 /// The severity of a message
+///
+/// Levels are ordered by severity, from `Debug` to `Failure`. More levels may be added in future.
+///
+/// ```rust
+/// use prodash::tree::MessageLevel;
+/// assert!(MessageLevel::Debug < MessageLevel::Success);
+/// assert!(MessageLevel::Success < MessageLevel::Warning);
+/// assert!(MessageLevel::Warning < MessageLevel::Failure);
+/// ```
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd)]
+#[non_exhaustive]
 pub enum MessageLevel {
+    /// Details which are only of interest when investigating an issue
+    Debug,
     /// Rarely sent information related to the progress, not to be confused with the progress itself
     Info,
-    /// Used to indicate that a task has failed, along with the reason
-    Failure,
     /// Indicates a task was completed successfully
     Success,
+    /// Indicates a problem which doesn't keep the task from making progress
+    Warning,
+    /// Used to indicate that a task has failed, along with the reason
+    Failure,
 }
 
 /// A message to be stored along with the progress tree.
 ///
-/// It is created by [`Tree::message(…)`](./struct.Item.html#method.message).
+/// It is created by [`Tree::message(…)`](./struct.Item.html#method.message), or with [`Message::new(…)`](#method.new)
+/// as more fields may be added in future.
 #[derive(Debug, Clone)]
+#[non_exhaustive]
 pub struct Message {
     /// The time at which the message was sent.
     pub time: SystemTime,
@@ -82,12 +141,127 @@ pub struct Message {
     pub origin: String,
     /// The message itself
     pub message: String,
+    /// Key/value pairs providing machine-readable context, like the name and version of a crate.
+    pub fields: Vec<(String, String)>,
+}
+
+impl Message {
+    /// Create a message of the given `level`, sent by `origin` just now, without fields.
+    pub fn new(level: MessageLevel, origin: impl Into<String>, message: impl Into<String>) -> Self {
+        Message {
+            time: SystemTime::now(),
+            level,
+            origin: origin.into(),
+            message: message.into(),
+            fields: Vec::new(),
+        }
+    }
+}
+
+impl MessageLevel {
+    /// Returns a name of four characters, as used in columns.
+    pub fn short_name(self) -> &'static str {
+        use MessageLevel::*;
+        match self {
+            Debug => "debg",
+            Info => "info",
+            Warning => "warn",
+            Failure => "fail",
+            Success => "done",
+        }
+    }
+}
+
+/// Displays message fields as `key=value` pairs separated by spaces, quoting values which are empty or contain
+/// whitespace, quotes or `=`.
+pub(crate) struct FieldsDisplay<'a>(pub &'a [(String, String)]);
+
+impl<'a> fmt::Display for FieldsDisplay<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (index, (key, value)) in self.0.iter().enumerate() {
+            if index > 0 {
+                f.write_str(" ")?;
+            }
+            if value.is_empty()
+                || value.contains(|c: char| c.is_whitespace() || c == '"' || c == '=')
+            {
+                write!(f, "{}={:?}", key, value)?;
+            } else {
+                write!(f, "{}={}", key, value)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Formats the message as single line with its time, level, origin and fields, as used when exporting messages.
+///
+/// ```rust
+/// use prodash::tree::{Message, MessageLevel};
+/// use std::time::{Duration, UNIX_EPOCH};
+/// let mut message = Message::new(MessageLevel::Failure, "download", "connection reset");
+/// message.time = UNIX_EPOCH + Duration::from_secs(1_581_555_105);
+/// message.fields = vec![("crate".into(), "serde".into()), ("reason".into(), "timed out".into())];
+/// assert_eq!(
+///     message.to_string(),
+///     r#"2020-02-13T00:51:45Z fail download → connection reset crate=serde reason="timed out""#
+/// );
+/// ```
+impl fmt::Display for Message {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} {} {} → {}",
+            humantime::format_rfc3339_seconds(self.time),
+            self.level.short_name(),
+            self.origin,
+            self.message
+        )?;
+        if !self.fields.is_empty() {
+            write!(f, " {}", FieldsDisplay(&self.fields))?;
+        }
+        Ok(())
+    }
+}
+
+/// The amount of messages sent of each level, as returned by
+/// [`Root::message_counts()`](./struct.Root.html#method.message_counts).
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct MessageCounts {
+    /// The amount of debug messages
+    pub debug: usize,
+    /// The amount of informational messages
+    pub info: usize,
+    /// The amount of warnings
+    pub warning: usize,
+    /// The amount of failures, usually one per failed task
+    pub failure: usize,
+    /// The amount of success messages, usually one per finished task
+    pub success: usize,
+}
+
+impl MessageCounts {
+    fn total(&self) -> usize {
+        self.debug + self.info + self.warning + self.failure + self.success
+    }
+
+    fn count(&mut self, level: MessageLevel) {
+        use MessageLevel::*;
+        *match level {
+            Debug => &mut self.debug,
+            Info => &mut self.info,
+            Warning => &mut self.warning,
+            Failure => &mut self.failure,
+            Success => &mut self.success,
+        } += 1;
+    }
 }
 
 #[derive(Debug)]
 pub(crate) struct MessageRingBuffer {
     buf: Vec<Message>,
     cursor: usize,
+    counts: MessageCounts,
 }
 
 impl MessageRingBuffer {
@@ -95,6 +269,7 @@ impl MessageRingBuffer {
         MessageRingBuffer {
             buf: Vec::with_capacity(capacity),
             cursor: 0,
+            counts: MessageCounts::default(),
         }
     }
 
@@ -103,12 +278,17 @@ impl MessageRingBuffer {
     }
 
     pub fn push_overwrite(&mut self, level: MessageLevel, origin: String, message: &str) {
-        let msg = Message {
+        self.push_message(Message {
             time: SystemTime::now(),
             level,
             origin,
             message: message.to_string(),
-        };
+            fields: Vec::new(),
+        })
+    }
+
+    pub fn push_message(&mut self, msg: Message) {
+        self.counts.count(msg.level);
         if self.has_capacity() {
             self.buf.push(msg)
         } else {
@@ -117,6 +297,13 @@ impl MessageRingBuffer {
         }
     }
 
+    #[cfg(feature = "recording")]
+    pub fn clear(&mut self) {
+        self.buf.clear();
+        self.cursor = 0;
+        self.counts = MessageCounts::default();
+    }
+
     pub fn copy_into(&self, out: &mut Vec<Message>) {
         out.clear();
         if self.has_capacity() {
@@ -137,7 +324,7 @@ impl MessageRingBuffer {
 /// let tree = prodash::Tree::new();
 /// let mut progress = tree.add_child("task 1");
 ///
-/// progress.init(Some(10), Some("elements"));
+/// progress.init(Some(10), Some("elements".into()));
 /// for p in 0..10 {
 ///     progress.set(p);
 /// }
@@ -153,11 +340,71 @@ pub struct Item {
     pub(crate) highest_child_id: ItemId,
     pub(crate) tree: Arc<DashMap<Key, Value>>,
     pub(crate) messages: Arc<Mutex<MessageRingBuffer>>,
+    pub(crate) retention: Option<Arc<Retention>>,
+    /// The outcome of the task according to the last success or failure message it sent.
+    pub(crate) outcome: Option<Outcome>,
 }
 
 impl Drop for Item {
     fn drop(&mut self) {
-        self.tree.remove(&self.key);
+        match &self.retention {
+            Some(retention) => {
+                let outcome = Some(self.outcome.unwrap_or(Outcome::Cancelled));
+                let exists = self
+                    .tree
+                    .get_mut(&self.key)
+                    .map(|mut r| r.value_mut().outcome = outcome)
+                    .is_some();
+                if exists {
+                    retention.retain(self.key.clone());
+                }
+                retention.remove_expired(&self.tree);
+            }
+            None => {
+                self.tree.remove(&self.key);
+            }
+        }
+    }
+}
+
+/// Keeps the values of dropped items in the tree until their time is up.
+///
+/// Expired values are removed whenever items are added or dropped, and before snapshots are taken.
+#[derive(Debug)]
+pub(crate) struct Retention {
+    duration: Duration,
+    /// Keys in the order they expire in, as all of them are kept for the same duration.
+    expiring: Mutex<VecDeque<(Instant, Key)>>,
+}
+
+impl Retention {
+    pub fn new(duration: Duration) -> Self {
+        Retention {
+            duration,
+            expiring: Default::default(),
+        }
+    }
+
+    fn retain(&self, key: Key) {
+        self.expiring
+            .lock()
+            .push_back((Instant::now() + self.duration, key));
+    }
+
+    fn remove_expired(&self, tree: &DashMap<Key, Value>) {
+        let now = Instant::now();
+        let expired: Vec<_> = {
+            let mut expiring = self.expiring.lock();
+            let num_expired = expiring
+                .iter()
+                .take_while(|(expires_at, _)| *expires_at <= now)
+                .count();
+            expiring.drain(..num_expired).map(|(_, key)| key).collect()
+        };
+        // The tree is only changed once the lock is released, as dropped items lock the tree first.
+        for key in expired {
+            tree.remove(&key);
+        }
     }
 }
 
@@ -169,13 +416,15 @@ impl Item {
     /// If `max` is `None`, the progress is unbounded. Use this if the amount of work cannot accurately
     /// be determined.
     ///
-    /// If `unit` is `Some(…)`, it is used for display purposes only. It should be using the plural.
+    /// If `unit` is `Some(…)`, it is used for display purposes only. Use [`unit::label(…)`](../unit/fn.label.html)
+    /// for plain labels like "items", which should be using the plural, or any other [`Unit`](../unit/enum.Unit.html)
+    /// to display values like bytes or durations in a human-readable way.
     ///
     /// If this method is never called, this `Item` will serve as organizational unit, useful to add more structure
     /// to the progress tree.
     ///
     /// **Note** that this method can be called multiple times, changing the bounded-ness and unit at will.
-    pub fn init(&mut self, max: Option<ProgressStep>, unit: Option<&'static str>) {
+    pub fn init(&mut self, max: Option<ProgressStep>, unit: Option<Unit>) {
         self.tree.get_mut(&self.key).map(|mut r| {
             r.value_mut().progress = Some(Progress {
                 done_at: max,
@@ -187,11 +436,6 @@ impl Item {
 
     fn alter_progress(&mut self, f: impl FnMut(&mut Progress)) {
         self.tree.get_mut(&self.key).map(|mut r| {
-            // NOTE: since we wrap around, if there are more tasks than we can have IDs for,
-            // and if all these tasks are still alive, two progress trees may see the same ID
-            // when these go out of scope, they delete the key and the other tree will not find
-            // its value anymore. Besides, it's probably weird to see tasks changing their progress
-            // all the time…
             r.value_mut().progress.as_mut().map(f);
         });
     }
@@ -213,6 +457,23 @@ impl Item {
         });
     }
 
+    /// Increment the current progress by the given `step`.
+    ///
+    /// **Note**: that this call has no effect unless `init(…)` was called before.
+    pub fn inc_by(&mut self, step: ProgressStep) {
+        self.alter_progress(|p| {
+            p.step = p.step.saturating_add(step);
+            p.state = ProgressState::Running;
+        });
+    }
+
+    /// Increment the current progress by one.
+    ///
+    /// **Note**: that this call has no effect unless `init(…)` was called before.
+    pub fn inc(&mut self) {
+        self.inc_by(1)
+    }
+
     /// Call to indicate that progress cannot be made.
     ///
     /// If `eta` is `Some(…)`, it specifies the time at which this task is expected to
@@ -225,24 +486,28 @@ impl Item {
 
     /// Adds a new child `Tree`, whose parent is this instance, with the given `name`.
     ///
-    /// **Important**: The depth of the hierarchy is limited to [`tree::Key::max_level`](./struct.Key.html#method.max_level).
-    /// Exceeding the level will be ignored, and new tasks will be added to this instance's
-    /// level instead.
+    /// There is no limit to the depth of the hierarchy.
     pub fn add_child(&mut self, name: impl Into<String>) -> Item {
+        if let Some(retention) = &self.retention {
+            retention.remove_expired(&self.tree);
+        }
         let child_key = self.key.add_child(self.highest_child_id);
         self.tree.insert(
-            child_key,
+            child_key.clone(),
             Value {
                 name: name.into(),
                 progress: None,
+                outcome: None,
             },
         );
-        self.highest_child_id = self.highest_child_id.wrapping_add(1);
+        self.highest_child_id += 1;
         Item {
             highest_child_id: 0,
             key: child_key,
             tree: self.tree.clone(),
             messages: self.messages.clone(),
+            retention: self.retention.clone(),
+            outcome: None,
         }
     }
 
@@ -251,14 +516,61 @@ impl Item {
     /// Use this to provide additional,human-readable information about the progress
     /// made, including indicating success or failure.
     pub fn message(&mut self, level: MessageLevel, message: impl AsRef<str>) {
-        self.messages.lock().push_overwrite(
+        self.track_outcome(level);
+        self.messages
+            .lock()
+            .push_overwrite(level, self.name(), message.as_ref())
+    }
+
+    /// Like [`message(…)`](#method.message), but with `fields` providing machine-readable context, like the name
+    /// and version of a crate.
+    ///
+    /// ```rust
+    /// use prodash::tree::MessageLevel;
+    /// let root = prodash::Tree::new();
+    /// let mut progress = root.add_child("download");
+    /// progress.message_with_fields(
+    ///     MessageLevel::Warning,
+    ///     "retrying",
+    ///     vec![("crate", "serde"), ("version", "1.0.104")],
+    /// );
+    /// let mut messages = Vec::new();
+    /// root.copy_messages(&mut messages);
+    /// assert_eq!(messages[0].fields[1], ("version".into(), "1.0.104".into()));
+    /// ```
+    pub fn message_with_fields(
+        &mut self,
+        level: MessageLevel,
+        message: impl AsRef<str>,
+        fields: impl IntoIterator<Item = (impl Into<String>, impl Into<String>)>,
+    ) {
+        self.track_outcome(level);
+        let message = Message {
+            time: SystemTime::now(),
             level,
-            self.tree
-                .get(&self.key)
-                .map(|v| v.name.to_owned())
-                .unwrap_or_default(),
-            message.as_ref(),
-        )
+            origin: self.name(),
+            message: message.as_ref().to_owned(),
+            fields: fields
+                .into_iter()
+                .map(|(key, value)| (key.into(), value.into()))
+                .collect(),
+        };
+        self.messages.lock().push_message(message)
+    }
+
+    fn track_outcome(&mut self, level: MessageLevel) {
+        match level {
+            MessageLevel::Success => self.outcome = Some(Outcome::Succeeded),
+            MessageLevel::Failure => self.outcome = Some(Outcome::Failed),
+            _ => {}
+        }
+    }
+
+    fn name(&self) -> String {
+        self.tree
+            .get(&self.key)
+            .map(|v| v.name.to_owned())
+            .unwrap_or_default()
     }
 
     /// Create a message indicating the task is done
@@ -275,50 +587,65 @@ impl Item {
     pub fn info(&mut self, message: impl AsRef<str>) {
         self.message(MessageLevel::Info, message)
     }
+
+    /// Create a message about a problem which doesn't keep the task from making progress.
+    pub fn warn(&mut self, message: impl AsRef<str>) {
+        self.message(MessageLevel::Warning, message)
+    }
+
+    /// Create a message with details which are only of interest when investigating an issue.
+    pub fn debug(&mut self, message: impl AsRef<str>) {
+        self.message(MessageLevel::Debug, message)
+    }
 }
 
-type ItemId = u16; // NOTE: This means we will show weird behaviour if there are more than 2^16 tasks at the same time on a level
-/// The amount of steps a progress can make
-pub type ProgressStep = u32;
+/// The ID of an item among its siblings. It is large enough to never wrap around in practice, so no two items share a key.
+type ItemId = u64;
+/// The amount of steps a progress can make, large enough to count the bytes of files beyond 4 GiB
+pub type ProgressStep = u64;
 
 /// A type identifying a spot in the hierarchy of `Tree` items.
-#[derive(Copy, Clone, Default, Hash, Eq, PartialEq, Ord, PartialOrd, Debug)]
-pub struct Key(
-    (
-        Option<ItemId>,
-        Option<ItemId>,
-        Option<ItemId>,
-        Option<ItemId>,
-    ),
-);
+///
+/// Keys are ordered such that each item comes right before its children, and children are ordered by their creation.
+/// Up to four levels are stored without allocating.
+#[derive(Clone, Default, Hash, Eq, PartialEq, Ord, PartialOrd, Debug)]
+pub struct Key(SmallVec<[ItemId; 4]>);
 
 impl Key {
-    fn add_child(self, child_id: ItemId) -> Key {
-        Key(match self {
-            Key((None, None, None, None)) => (Some(child_id), None, None, None),
-            Key((a, None, None, None)) => (a, Some(child_id), None, None),
-            Key((a, b, None, None)) => (a, b, Some(child_id), None),
-            Key((a, b, c, None)) => (a, b, c, Some(child_id)),
-            Key((a, b, c, _d)) => {
-                log::warn!("Maximum nesting level reached. Adding tasks to current parent");
-                (a, b, c, Some(child_id))
-            }
-        })
+    pub(crate) fn add_child(&self, child_id: ItemId) -> Key {
+        let mut ids = self.0.clone();
+        ids.push(child_id);
+        Key(ids)
     }
 
-    pub fn level(&self) -> u8 {
-        match self {
-            Key((None, None, None, None)) => 0,
-            Key((Some(_), None, None, None)) => 1,
-            Key((Some(_), Some(_), None, None)) => 2,
-            Key((Some(_), Some(_), Some(_), None)) => 3,
-            Key((Some(_), Some(_), Some(_), Some(_))) => 4,
-            _ => unreachable!("This is a bug - Keys follow a certain pattern"),
-        }
+    #[cfg(feature = "recording")]
+    pub(crate) fn from_ids(ids: &[ItemId]) -> Key {
+        Key(ids.into())
     }
 
+    #[cfg(any(
+        feature = "recording",
+        feature = "tui-renderer",
+        feature = "http-exporter"
+    ))]
+    pub(crate) fn ids(&self) -> &[ItemId] {
+        &self.0
+    }
+
+    /// Returns the depth of the item in the hierarchy, with `1` being an item added to the `Root`.
+    pub fn level(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Returns `u8::MAX`, as there is no limit to the depth of the hierarchy anymore.
+    #[deprecated(note = "there is no limit to the depth of the hierarchy anymore")]
     pub const fn max_level() -> u8 {
-        4
+        u8::MAX
+    }
+
+    /// Returns true if this key belongs to a parent, grandparent or any other ancestor of the item with the `other` key.
+    pub fn is_ancestor_of(&self, other: &Key) -> bool {
+        self.0.len() < other.0.len() && other.0.starts_with(&self.0)
     }
 }
 
@@ -339,7 +666,9 @@ impl Default for ProgressState {
 }
 
 /// Progress associated with some item in the progress tree.
-#[derive(Copy, Clone, Default, Hash, Eq, PartialEq, Ord, PartialOrd, Debug)]
+///
+/// It is neither `Copy` nor ordered, as its [`Unit`](../unit/enum.Unit.html) can't be.
+#[derive(Clone, Default, Hash, Eq, PartialEq, Debug)]
 pub struct Progress {
     /// The amount of progress currently made
     pub step: ProgressStep,
@@ -348,9 +677,11 @@ pub struct Progress {
     /// If unset, the progress is unbounded.
     pub done_at: Option<ProgressStep>,
     /// The unit associated with the progress.
-    pub unit: Option<&'static str>,
+    pub unit: Option<Unit>,
     /// Whether progress can be made or not
     pub state: ProgressState,
+    /// The rate at which progress was made recently, available only in [snapshots](./struct.Root.html#method.sorted_snapshot).
+    pub throughput: Option<Throughput>,
 }
 
 impl Progress {
@@ -364,10 +695,137 @@ impl Progress {
 }
 
 /// The value associated with a spot in the hierarchy.
-#[derive(Clone, Default, Hash, Eq, PartialEq, Ord, PartialOrd, Debug)]
+#[derive(Clone, Default, Hash, Eq, PartialEq, Debug)]
 pub struct Value {
     /// The name of the `Item` or task.
     pub name: String,
     /// The progress itself, unless this value belongs to an `Item` serving as organizational unit.
     pub progress: Option<Progress>,
+    /// How the task ended, which is only set while it is
+    /// [retained](../struct.TreeOptions.html#structfield.retention) after its `Item` was dropped.
+    pub outcome: Option<Outcome>,
+}
+
+/// How a task ended, as determined when its `Item` is dropped.
+#[derive(Copy, Clone, Hash, Eq, PartialEq, Ord, PartialOrd, Debug)]
+pub enum Outcome {
+    /// The last success or failure message sent by the task indicated success, as with
+    /// [`Item::done(…)`](./struct.Item.html#method.done).
+    Succeeded,
+    /// The last success or failure message sent by the task indicated failure, as with
+    /// [`Item::fail(…)`](./struct.Item.html#method.fail).
+    Failed,
+    /// The task didn't send any success or failure message.
+    Cancelled,
+}
+
+impl fmt::Display for Outcome {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            Outcome::Succeeded => "succeeded",
+            Outcome::Failed => "failed",
+            Outcome::Cancelled => "cancelled",
+        })
+    }
+}
+
+/// The rate at which progress is made, measured over a sliding window of recent steps.
+#[derive(Copy, Clone, Default, Hash, Eq, PartialEq, Ord, PartialOrd, Debug)]
+pub struct Throughput {
+    /// The amount of steps made within `timespan`.
+    pub steps: ProgressStep,
+    /// The amount of time in which `steps` were made.
+    pub timespan: Duration,
+    /// The time at which the progress is expected to be done, if the progress is bounded and advancing.
+    pub eta: Option<SystemTime>,
+}
+
+impl Throughput {
+    /// Returns the amount of steps made per second.
+    pub fn per_second(&self) -> f32 {
+        self.steps as f32 / self.timespan.as_secs_f32()
+    }
+}
+
+/// The amount of time covered by the samples used to compute the throughput.
+const THROUGHPUT_WINDOW: Duration = Duration::from_secs(10);
+/// The minimal amount of time between two samples, protecting against snapshots being taken very frequently.
+const THROUGHPUT_SAMPLE_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Samples the progress of tasks to compute their [`Throughput`](./struct.Throughput.html), which is done by each
+/// [`Root::sorted_snapshot(…)`](./struct.Root.html#method.sorted_snapshot).
+///
+/// Doing it there instead of when progress is set keeps `Item::set(…)` as fast as it was, at the cost of depending
+/// on snapshots for the samples. All snapshots are sampled, no matter if they are taken by renderers, recorders or
+/// the HTTP exporter, but a task is sampled at most once per 100ms. As the throughput is computed from the first and
+/// last sample within the last 10 seconds, it doesn't depend on how often snapshots are taken as long as it happens
+/// at least once per second or so.
+#[derive(Default, Debug)]
+pub struct ThroughputTracker {
+    samples: HashMap<Key, VecDeque<(Instant, ProgressStep)>>,
+}
+
+impl ThroughputTracker {
+    /// Sample the progress of all `entries`, which must be sorted by key, and set their throughput.
+    pub fn update(&mut self, entries: &mut [(Key, Value)]) {
+        self.update_at(entries, Instant::now(), SystemTime::now())
+    }
+
+    /// Like [`update(…)`](#method.update), but sampling at the given time `now`, which is `wall_clock_now` in
+    /// system time to compute the estimated time of completion.
+    pub fn update_at(
+        &mut self,
+        entries: &mut [(Key, Value)],
+        now: Instant,
+        wall_clock_now: SystemTime,
+    ) {
+        for (key, value) in entries.iter_mut() {
+            let progress = match value.progress.as_mut() {
+                Some(p) => p,
+                None => continue,
+            };
+            let samples = self.samples.entry(key.clone()).or_default();
+            if matches!(samples.back(), Some(&(_, step)) if step > progress.step) {
+                // the progress was reset, and prior samples don't apply anymore.
+                samples.clear();
+            }
+            match samples.back() {
+                Some(&(time, _)) if now.duration_since(time) < THROUGHPUT_SAMPLE_INTERVAL => {}
+                _ => samples.push_back((now, progress.step)),
+            }
+            while samples.len() > 2
+                && matches!(samples.front(), Some(&(time, _)) if now.duration_since(time) > THROUGHPUT_WINDOW)
+            {
+                samples.pop_front();
+            }
+
+            progress.throughput = match (samples.front(), samples.back()) {
+                (Some(&(first_time, first_step)), Some(&(last_time, last_step)))
+                    if last_time > first_time =>
+                {
+                    let steps = last_step - first_step;
+                    let timespan = last_time.duration_since(first_time);
+                    Some(Throughput {
+                        steps,
+                        timespan,
+                        eta: match progress.done_at {
+                            // Far away or not representable ETAs are dropped instead of panicking.
+                            Some(done_at) if steps > 0 && done_at >= progress.step => {
+                                Duration::try_from_secs_f64(
+                                    timespan.as_secs_f64() * (done_at - progress.step) as f64
+                                        / steps as f64,
+                                )
+                                .ok()
+                                .and_then(|remaining| wall_clock_now.checked_add(remaining))
+                            }
+                            _ => None,
+                        },
+                    })
+                }
+                _ => None,
+            };
+        }
+        self.samples
+            .retain(|key, _| entries.binary_search_by(|e| e.0.cmp(key)).is_ok());
+    }
 }
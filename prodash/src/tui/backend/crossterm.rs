@@ -0,0 +1,185 @@
+use crate::tui::input::Key;
+use crossterm::{
+    cursor::{Hide, MoveTo, Show},
+    event::{self, Event},
+    execute, queue,
+    style::{
+        Attribute, Color as CColor, Print, SetAttribute, SetBackgroundColor, SetForegroundColor,
+    },
+    terminal::{self, Clear, ClearType, EnterAlternateScreen, LeaveAlternateScreen},
+};
+use futures::{channel::mpsc, SinkExt};
+use std::io::{self, Write};
+use tui::{
+    backend::Backend,
+    buffer::Cell,
+    layout::Rect,
+    style::{Color, Modifier, Style},
+};
+use tui_react::Terminal;
+
+/// Returns a terminal in raw mode on the alternate screen, which are both left once it is dropped.
+pub(crate) fn new_terminal() -> io::Result<Terminal<impl Backend>> {
+    Terminal::new(CrosstermBackend::new()?)
+}
+
+/// Returns a stream of the keys pressed by the user.
+pub(crate) fn key_input_stream() -> mpsc::Receiver<Key> {
+    let (mut key_send, key_receive) = mpsc::channel::<Key>(1);
+
+    // This brings blocking key-handling into the async world
+    std::thread::spawn(move || -> crossterm::Result<()> {
+        loop {
+            if let Event::Key(key) = event::read()? {
+                if futures::executor::block_on(key_send.send(key.into())).is_err() {
+                    break;
+                }
+            }
+        }
+        Ok(())
+    });
+    key_receive
+}
+
+/// A backend for `tui` writing to stdout with `crossterm`.
+///
+/// `tui` provides its own, but for a version of `crossterm` which is incompatible with the one used here.
+struct CrosstermBackend {
+    out: io::BufWriter<io::Stdout>,
+}
+
+impl CrosstermBackend {
+    fn new() -> io::Result<Self> {
+        terminal::enable_raw_mode().map_err(into_io_error)?;
+        let mut out = io::BufWriter::new(io::stdout());
+        execute!(out, EnterAlternateScreen).map_err(into_io_error)?;
+        Ok(CrosstermBackend { out })
+    }
+}
+
+impl Drop for CrosstermBackend {
+    fn drop(&mut self) {
+        execute!(self.out, LeaveAlternateScreen).ok();
+        terminal::disable_raw_mode().ok();
+    }
+}
+
+/// All modifiers along with the attribute enabling them.
+const ATTRIBUTES: &[(Modifier, Attribute)] = &[
+    (Modifier::BOLD, Attribute::Bold),
+    (Modifier::DIM, Attribute::Dim),
+    (Modifier::ITALIC, Attribute::Italic),
+    (Modifier::UNDERLINED, Attribute::Underlined),
+    (Modifier::SLOW_BLINK, Attribute::SlowBlink),
+    (Modifier::RAPID_BLINK, Attribute::RapidBlink),
+    (Modifier::REVERSED, Attribute::Reverse),
+    (Modifier::HIDDEN, Attribute::Hidden),
+    (Modifier::CROSSED_OUT, Attribute::CrossedOut),
+];
+
+impl Backend for CrosstermBackend {
+    fn draw<'a, I>(&mut self, content: I) -> io::Result<()>
+    where
+        I: Iterator<Item = (u16, u16, &'a Cell)>,
+    {
+        let mut style = Style::default();
+        let mut last_position = None;
+        for (x, y, cell) in content {
+            if last_position != Some((x.wrapping_sub(1), y)) {
+                queue!(self.out, MoveTo(x, y)).map_err(into_io_error)?;
+            }
+            last_position = Some((x, y));
+            if cell.style.modifier != style.modifier {
+                // resetting attributes also resets colors, which are set again below
+                queue!(self.out, SetAttribute(Attribute::Reset)).map_err(into_io_error)?;
+                for (modifier, attribute) in ATTRIBUTES {
+                    if cell.style.modifier.contains(*modifier) {
+                        queue!(self.out, SetAttribute(*attribute)).map_err(into_io_error)?;
+                    }
+                }
+                style = Style {
+                    modifier: cell.style.modifier,
+                    ..Style::default()
+                };
+            }
+            if cell.style.fg != style.fg {
+                queue!(self.out, SetForegroundColor(to_color(cell.style.fg)))
+                    .map_err(into_io_error)?;
+                style.fg = cell.style.fg;
+            }
+            if cell.style.bg != style.bg {
+                queue!(self.out, SetBackgroundColor(to_color(cell.style.bg)))
+                    .map_err(into_io_error)?;
+                style.bg = cell.style.bg;
+            }
+            queue!(self.out, Print(cell.symbol.as_str())).map_err(into_io_error)?;
+        }
+        queue!(
+            self.out,
+            SetForegroundColor(CColor::Reset),
+            SetBackgroundColor(CColor::Reset),
+            SetAttribute(Attribute::Reset)
+        )
+        .map_err(into_io_error)
+    }
+
+    fn hide_cursor(&mut self) -> io::Result<()> {
+        execute!(self.out, Hide).map_err(into_io_error)
+    }
+
+    fn show_cursor(&mut self) -> io::Result<()> {
+        execute!(self.out, Show).map_err(into_io_error)
+    }
+
+    fn get_cursor(&mut self) -> io::Result<(u16, u16)> {
+        crossterm::cursor::position().map_err(into_io_error)
+    }
+
+    fn set_cursor(&mut self, x: u16, y: u16) -> io::Result<()> {
+        execute!(self.out, MoveTo(x, y)).map_err(into_io_error)
+    }
+
+    fn clear(&mut self) -> io::Result<()> {
+        execute!(self.out, Clear(ClearType::All)).map_err(into_io_error)
+    }
+
+    fn size(&self) -> io::Result<Rect> {
+        let (width, height) = terminal::size().map_err(into_io_error)?;
+        Ok(Rect::new(0, 0, width, height))
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.out.flush()
+    }
+}
+
+fn into_io_error(err: crossterm::ErrorKind) -> io::Error {
+    match err {
+        crossterm::ErrorKind::IoError(err) => err,
+        err => io::Error::other(err.to_string()),
+    }
+}
+
+fn to_color(color: Color) -> CColor {
+    match color {
+        Color::Reset => CColor::Reset,
+        Color::Black => CColor::Black,
+        Color::Red => CColor::DarkRed,
+        Color::Green => CColor::DarkGreen,
+        Color::Yellow => CColor::DarkYellow,
+        Color::Blue => CColor::DarkBlue,
+        Color::Magenta => CColor::DarkMagenta,
+        Color::Cyan => CColor::DarkCyan,
+        Color::Gray => CColor::Grey,
+        Color::DarkGray => CColor::DarkGrey,
+        Color::LightRed => CColor::Red,
+        Color::LightGreen => CColor::Green,
+        Color::LightBlue => CColor::Blue,
+        Color::LightYellow => CColor::Yellow,
+        Color::LightMagenta => CColor::Magenta,
+        Color::LightCyan => CColor::Cyan,
+        Color::White => CColor::White,
+        Color::Indexed(index) => CColor::AnsiValue(index),
+        Color::Rgb(r, g, b) => CColor::Rgb { r, g, b },
+    }
+}
@@ -0,0 +1,19 @@
+//! The terminal backends, selected with the `tui-renderer-termion` and `tui-renderer-crossterm` features.
+//!
+//! Each one provides a terminal in raw mode on the alternate screen, and a stream of keys pressed by the user.
+//! If both features are enabled, crossterm is used.
+#[cfg(feature = "tui-renderer-crossterm")]
+mod crossterm;
+#[cfg(feature = "tui-renderer-crossterm")]
+pub(crate) use self::crossterm::{key_input_stream, new_terminal};
+
+#[cfg(all(
+    feature = "tui-renderer-termion",
+    not(feature = "tui-renderer-crossterm")
+))]
+mod termion;
+#[cfg(all(
+    feature = "tui-renderer-termion",
+    not(feature = "tui-renderer-crossterm")
+))]
+pub(crate) use self::termion::{key_input_stream, new_terminal};
@@ -0,0 +1,29 @@
+use crate::tui::input::Key;
+use futures::{channel::mpsc, SinkExt};
+use std::io;
+use termion::{input::TermRead, raw::IntoRawMode, screen::AlternateScreen};
+use tui::backend::{Backend, TermionBackend};
+use tui_react::Terminal;
+
+/// Returns a terminal in raw mode on the alternate screen, which are both left once it is dropped.
+pub(crate) fn new_terminal() -> io::Result<Terminal<impl Backend>> {
+    let stdout = io::stdout().into_raw_mode()?;
+    let stdout = AlternateScreen::from(stdout);
+    let backend = TermionBackend::new(stdout);
+    Terminal::new(backend)
+}
+
+/// Returns a stream of the keys pressed by the user.
+pub(crate) fn key_input_stream() -> mpsc::Receiver<Key> {
+    let (mut key_send, key_receive) = mpsc::channel::<Key>(1);
+
+    // This brings blocking key-handling into the async world
+    std::thread::spawn(move || -> Result<(), io::Error> {
+        for key in io::stdin().keys() {
+            let key = key?;
+            futures::executor::block_on(key_send.send(key.into())).ok();
+        }
+        Ok(())
+    });
+    key_receive
+}
@@ -3,28 +3,64 @@ use crate::{
     tui::{
         draw,
         utils::{block_width, rect},
-        Line,
+        KeyMap, Line, Theme,
     },
 };
-use std::time::Duration;
+use std::{
+    collections::HashSet,
+    time::{Duration, SystemTime},
+};
 use tui::{
     buffer::Buffer,
     layout::Rect,
     widgets::{Block, Borders, Widget},
 };
 
+/// The state of the terminal user interface, as changed by user input and events.
 #[derive(Default)]
 pub struct State {
+    /// The title of the whole window.
     pub title: String,
+    /// The amount of tasks scrolled past in the progress pane.
     pub task_offset: u16,
+    /// The amount of messages scrolled past in the messages pane.
     pub message_offset: u16,
+    /// If true, the messages pane isn't shown.
     pub hide_messages: bool,
+    /// If true, the messages pane takes most of the space.
     pub messages_fullscreen: bool,
+    /// Determines which messages are shown in the messages pane.
+    pub message_filter: draw::MessageFilter,
+    /// The window size set with [`Event::SetWindowSize`](../enum.Event.html#variant.SetWindowSize), if any.
     pub user_provided_window_size: Option<Rect>,
+    /// The time between two frames, which is displayed in the headline if it is longer than a second.
     pub duration_per_frame: Duration,
+    /// The lines to show in the information pane.
     pub information: Vec<Line>,
+    /// If true, the information pane isn't shown.
     pub hide_info: bool,
+    /// If true, the information pane takes as much space as it needs.
     pub maximize_info: bool,
+    /// Determines which tasks are shown in the progress pane.
+    pub task_filter: draw::TaskFilter,
+    /// The task selected in the progress pane, which is kept even if the task doesn't exist anymore.
+    pub selected: Option<Key>,
+    /// If true, the progress pane scrolls to the selected task once.
+    pub follow_selection: bool,
+    /// The tasks whose descendants are replaced by a summary line.
+    pub collapsed: HashSet<Key>,
+    /// If set, only the task with this key and its descendants are shown.
+    pub focused: Option<Key>,
+    /// The keys bound to actions, which are shown in hints and in the help overlay.
+    pub key_map: KeyMap,
+    /// If true, all key bindings are shown on top of everything else.
+    pub show_help: bool,
+    /// The colors and modifiers to draw with.
+    pub theme: Theme,
+    /// If true, a bar with the combined progress of all tasks is shown above them.
+    pub show_summary: bool,
+    /// The time at which the work started, to show the elapsed time in the summary.
+    pub started_at: Option<SystemTime>,
 }
 
 pub fn all(
@@ -64,23 +100,61 @@ pub fn all(
         ),
     );
 
-    let inner = window.inner(bound);
+    let mut inner = window.inner(bound);
+    if state.show_summary && inner.height > draw::summary::HEIGHT * 2 {
+        draw::summary::pane(entries, state.started_at, &state.theme, inner, buf);
+        inner.y += draw::summary::HEIGHT;
+        inner.height -= draw::summary::HEIGHT;
+    }
     let (tasks_pane, messages_pane) = compute_pane_bounds(
         if state.hide_messages { &[] } else { messages },
         inner,
         state.messages_fullscreen,
     );
 
-    draw::progress::pane(&entries, tasks_pane, &mut state.task_offset, buf);
+    forget_vanished_tasks(state, entries);
+    let visible_entries = draw::filter::visible_entries(state, entries);
+    let tasks_pane = if state.task_filter.is_active() && tasks_pane.height > 1 {
+        let status_line = rect::line_bound(tasks_pane, tasks_pane.height as usize - 1);
+        draw::filter::status(
+            &state.task_filter,
+            visible_entries
+                .iter()
+                .filter(|(key, _)| !draw::filter::is_summary(key))
+                .count(),
+            entries.len(),
+            &state.theme,
+            status_line,
+            buf,
+        );
+        Rect {
+            height: tasks_pane.height - 1,
+            ..tasks_pane
+        }
+    } else {
+        tasks_pane
+    };
+    if state.follow_selection {
+        scroll_to_selection(state, &visible_entries, tasks_pane.height);
+    }
+    draw::progress::pane(
+        &visible_entries,
+        tasks_pane,
+        &mut state.task_offset,
+        state.selected.as_ref(),
+        &state.key_map,
+        &state.theme,
+        buf,
+    );
     if let Some(messages_pane) = messages_pane {
         draw::messages::pane(
             messages,
+            state,
             messages_pane,
             Rect {
                 width: messages_pane.width + 2,
                 ..rect::line_bound(bound, bound.height.saturating_sub(1) as usize)
             },
-            &mut state.message_offset,
             buf,
         );
     }
@@ -88,6 +162,38 @@ pub fn all(
     if let Some(info_pane) = info_pane {
         draw::information::pane(&state.information, info_pane, buf);
     }
+
+    if state.show_help {
+        draw::help::overlay(&state.key_map, &state.theme, bound, buf);
+    }
+}
+
+/// Keys are never reused, so collapsed or focused tasks which don't exist anymore won't come back.
+fn forget_vanished_tasks(state: &mut State, entries: &[(Key, Value)]) {
+    let exists = |key: &Key| entries.binary_search_by(|e| e.0.cmp(key)).is_ok();
+    state.collapsed.retain(|key| exists(key));
+    if !matches!(&state.focused, Some(key) if exists(key)) {
+        state.focused = None;
+    }
+}
+
+fn scroll_to_selection(state: &mut State, visible_entries: &[(Key, Value)], pane_height: u16) {
+    state.follow_selection = false;
+    let index = match state
+        .selected
+        .as_ref()
+        .and_then(|selected| visible_entries.binary_search_by(|e| e.0.cmp(selected)).ok())
+    {
+        Some(index) => index as u16,
+        None => return,
+    };
+    // leave room for the line shown if not all tasks fit
+    let visible_lines = pane_height.saturating_sub(1).max(1);
+    if index < state.task_offset {
+        state.task_offset = index;
+    } else if index >= state.task_offset + visible_lines {
+        state.task_offset = index + 1 - visible_lines;
+    }
 }
 
 fn compute_pane_bounds(
@@ -0,0 +1,231 @@
+use crate::{
+    tree::{Key, Progress, ProgressState, ProgressStep, Value},
+    tui::{draw::State, utils::draw_text_nowrap, Theme},
+};
+use std::{borrow::Cow, collections::HashSet};
+use tui::{buffer::Buffer, layout::Rect};
+
+/// Determines which tasks are shown in the progress pane.
+#[derive(Default, Clone, Debug)]
+pub struct TaskFilter {
+    /// Only tasks whose name contains this text, ignoring case, are shown, along with their ancestors.
+    pub text: String,
+    /// If true, keys are used to edit the `text`.
+    pub is_editing: bool,
+    /// If true, tasks whose progress reached its upper bound are hidden.
+    pub hide_finished: bool,
+    /// If true, tasks which didn't make progress recently are hidden.
+    pub hide_idle: bool,
+    /// If true, blocked tasks are hidden.
+    pub hide_blocked: bool,
+}
+
+impl TaskFilter {
+    /// Returns true if tasks may be hidden, or if the filter is being edited.
+    pub fn is_active(&self) -> bool {
+        self.is_editing
+            || !self.text.is_empty()
+            || self.hide_finished
+            || self.hide_idle
+            || self.hide_blocked
+    }
+
+    fn matches(&self, lowercase_text: &str, value: &Value) -> bool {
+        let name_matches = value.name.to_lowercase().contains(lowercase_text);
+        match &value.progress {
+            None => !lowercase_text.is_empty() && name_matches,
+            Some(progress) => {
+                let has_ended = value.outcome.is_some();
+                let is_finished = has_ended
+                    || matches!(progress.done_at, Some(done_at) if progress.step >= done_at);
+                let is_blocked = !has_ended && matches!(progress.state, ProgressState::Blocked(_));
+                let is_idle = !is_finished
+                    && !is_blocked
+                    && !matches!(progress.throughput, Some(t) if t.steps > 0);
+                name_matches
+                    && !(self.hide_finished && is_finished)
+                    && !(self.hide_blocked && is_blocked)
+                    && !(self.hide_idle && is_idle)
+            }
+        }
+    }
+}
+
+/// The ID of the line summarizing the descendants of a collapsed task, which is never used by actual tasks.
+const SUMMARY_ID: u64 = u64::MAX;
+
+/// Returns true if the entry with `key` is a line summarizing the descendants of a collapsed task.
+pub fn is_summary(key: &Key) -> bool {
+    key.ids().last() == Some(&SUMMARY_ID)
+}
+
+/// Returns the `entries` to show in the progress pane according to the focused and collapsed tasks as well as the
+/// task filter in `state`, in hierarchical order.
+pub fn visible_entries<'a>(state: &State, entries: &'a [(Key, Value)]) -> Cow<'a, [(Key, Value)]> {
+    let mut visible = Cow::Borrowed(entries);
+    if let Some(focused) = &state.focused {
+        visible = Cow::Owned(
+            entries
+                .iter()
+                .filter(|(key, _)| key == focused || focused.is_ancestor_of(key))
+                .cloned()
+                .collect(),
+        );
+    }
+    if state.task_filter.is_active() {
+        visible = Cow::Owned(filter_entries(&state.task_filter, &visible));
+    }
+    if !state.collapsed.is_empty() {
+        visible = Cow::Owned(collapse_entries(&state.collapsed, &visible));
+    }
+    visible
+}
+
+/// Returns all `entries` matching the `filter`, along with their ancestors.
+fn filter_entries(filter: &TaskFilter, entries: &[(Key, Value)]) -> Vec<(Key, Value)> {
+    let text = filter.text.to_lowercase();
+    let mut is_visible = vec![false; entries.len()];
+    let mut ancestors = Vec::<usize>::new();
+    for (index, (key, value)) in entries.iter().enumerate() {
+        while matches!(ancestors.last(), Some(&ancestor) if !entries[ancestor].0.is_ancestor_of(key))
+        {
+            ancestors.pop();
+        }
+        if filter.matches(&text, value) {
+            is_visible[index] = true;
+            for &ancestor in ancestors.iter().rev() {
+                if is_visible[ancestor] {
+                    break;
+                }
+                is_visible[ancestor] = true;
+            }
+        }
+        ancestors.push(index);
+    }
+    entries
+        .iter()
+        .zip(is_visible)
+        .filter_map(|(entry, is_visible)| {
+            if is_visible {
+                Some(entry.clone())
+            } else {
+                None
+            }
+        })
+        .collect()
+}
+
+#[derive(Default)]
+struct Summary {
+    running: usize,
+    blocked: usize,
+    finished: usize,
+    bounded: usize,
+    fractions: f32,
+}
+
+impl Summary {
+    fn add(&mut self, progress: &Progress, has_ended: bool) {
+        let is_finished = matches!(progress.done_at, Some(done_at) if progress.step >= done_at);
+        match progress.state {
+            _ if has_ended => self.finished += 1,
+            ProgressState::Blocked(_) => self.blocked += 1,
+            ProgressState::Running if is_finished => self.finished += 1,
+            ProgressState::Running => self.running += 1,
+        }
+        if let Some(fraction) = progress.fraction() {
+            self.bounded += 1;
+            self.fractions += fraction.min(1.0);
+        }
+    }
+
+    fn into_value(self, hidden: usize) -> Value {
+        Value {
+            name: {
+                let counts: Vec<_> = [
+                    (self.running, "running"),
+                    (self.blocked, "blocked"),
+                    (self.finished, "done"),
+                ]
+                .iter()
+                .filter(|(count, _)| *count > 0)
+                .map(|(count, state)| format!("{} {}", count, state))
+                .collect();
+                if counts.is_empty() {
+                    format!("{} hidden", hidden)
+                } else {
+                    format!("{} hidden: {}", hidden, counts.join(", "))
+                }
+            },
+            progress: if self.bounded == 0 {
+                None
+            } else {
+                Some(Progress {
+                    step: (self.fractions / self.bounded as f32 * 100.0).round() as ProgressStep,
+                    done_at: Some(100),
+                    unit: Some("%".into()),
+                    ..Progress::default()
+                })
+            },
+            outcome: None,
+        }
+    }
+}
+
+/// Returns the `entries` with the descendants of `collapsed` tasks replaced by a line summarizing them.
+fn collapse_entries(collapsed: &HashSet<Key>, entries: &[(Key, Value)]) -> Vec<(Key, Value)> {
+    let mut out = Vec::with_capacity(entries.len());
+    let mut entries = entries.iter().peekable();
+    while let Some((key, value)) = entries.next() {
+        out.push((key.clone(), value.clone()));
+        if !collapsed.contains(key) {
+            continue;
+        }
+        let (mut summary, mut hidden) = (Summary::default(), 0);
+        while let Some((descendant, value)) = entries.peek() {
+            if !key.is_ancestor_of(descendant) {
+                break;
+            }
+            if let Some(progress) = &value.progress {
+                summary.add(progress, value.outcome.is_some());
+            }
+            hidden += 1;
+            entries.next();
+        }
+        if hidden > 0 {
+            out.push((key.add_child(SUMMARY_ID), summary.into_value(hidden)));
+        }
+    }
+    out
+}
+
+/// Draw a line showing the `filter` and how many of `total` tasks are `shown`.
+pub fn status(
+    filter: &TaskFilter,
+    shown: usize,
+    total: usize,
+    theme: &Theme,
+    bound: Rect,
+    buf: &mut Buffer,
+) {
+    let hidden: Vec<_> = [
+        (filter.hide_finished, "finished"),
+        (filter.hide_idle, "idle"),
+        (filter.hide_blocked, "blocked"),
+    ]
+    .iter()
+    .filter_map(|&(is_hidden, name)| if is_hidden { Some(name) } else { None })
+    .collect();
+    let mut text = if filter.is_editing {
+        format!("/{}▏", filter.text)
+    } else if filter.text.is_empty() {
+        "/ = filter".to_owned()
+    } else {
+        format!("filter: {}", filter.text)
+    };
+    if !hidden.is_empty() {
+        text.push_str(&format!(" │ hiding {}", hidden.join(", ")));
+    }
+    text.push_str(&format!(" │ {} of {} shown ", shown, total));
+    draw_text_nowrap(bound, buf, text, theme.status_line);
+}
@@ -0,0 +1,71 @@
+use crate::tui::{
+    utils::{block_width, draw_text_nowrap, rect},
+    Action, KeyMap, Theme,
+};
+use tui::{
+    buffer::Buffer,
+    layout::Rect,
+    widgets::{Block, Borders, Widget},
+};
+
+/// Draw a box in the middle of `bound` listing all actions of the `key_map` along with the keys triggering them.
+pub fn overlay(key_map: &KeyMap, theme: &Theme, bound: Rect, buf: &mut Buffer) {
+    let mut actions = Vec::<Action>::new();
+    for (_, action) in key_map.bindings() {
+        if !actions.contains(action) {
+            actions.push(*action);
+        }
+    }
+    let lines: Vec<_> = actions
+        .into_iter()
+        .map(|action| {
+            (
+                key_map
+                    .keys(action)
+                    .map(|key| key.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", "),
+                action.description(),
+            )
+        })
+        .collect();
+    let keys_width = lines
+        .iter()
+        .fold(0, |width, (keys, _)| width.max(block_width(keys)));
+    let description_width = lines.iter().fold(0, |width, (_, description)| {
+        width.max(block_width(description))
+    });
+
+    let (border, margin) = (1, 1);
+    let width = (keys_width + description_width + 2 * margin + 2 * border + 2).min(bound.width);
+    let height = (lines.len() as u16 + 2 * border).min(bound.height);
+    let bound = Rect {
+        x: bound.x + (bound.width - width) / 2,
+        y: bound.y + (bound.height - height) / 2,
+        width,
+        height,
+    };
+    for y in bound.top()..bound.bottom() {
+        for x in bound.left()..bound.right() {
+            buf.get_mut(x, y).reset();
+        }
+    }
+    let mut block = Block::default().title("Keys").borders(Borders::ALL);
+    block.draw(bound, buf);
+    let inner = rect::offset_x(block.inner(bound), margin);
+    for (line, (keys, description)) in lines.iter().enumerate().take(inner.height as usize) {
+        let line_bound = rect::line_bound(inner, line);
+        draw_text_nowrap(
+            rect::offset_x(line_bound, keys_width - block_width(keys)),
+            buf,
+            keys,
+            theme.help_key,
+        );
+        draw_text_nowrap(
+            rect::offset_x(line_bound, keys_width + 2),
+            buf,
+            description,
+            None,
+        );
+    }
+}
@@ -1,12 +1,15 @@
 use crate::{
-    tree::{Message, MessageLevel},
-    tui::utils::{block_width, draw_text_nowrap, rect, sanitize_offset},
+    tree::{FieldsDisplay, Message, MessageLevel},
+    tui::{
+        draw::State,
+        utils::{block_width, draw_text_nowrap, rect, sanitize_offset},
+        Action,
+    },
 };
-use std::time::SystemTime;
+use std::{borrow::Cow, collections::BTreeSet, time::SystemTime};
 use tui::{
     buffer::Buffer,
     layout::Rect,
-    style::{Color, Modifier, Style},
     widgets::{Block, Borders, Widget},
 };
 use unicode_width::UnicodeWidthStr;
@@ -14,18 +17,134 @@ use unicode_width::UnicodeWidthStr;
 const TIME_COLUMN_PREFIX: u16 = "20-02-13T".len() as u16;
 const TIME_COLUMN_SUFFIX: u16 = "00:51:45".len() as u16;
 
+/// Determines which messages are shown in the messages pane.
+#[derive(Default, Clone, Debug)]
+pub struct MessageFilter {
+    /// Only messages whose text or field values contain this text, ignoring case, are shown.
+    pub text: String,
+    /// If true, keys are used to edit the `text`.
+    pub is_editing: bool,
+    /// If set, only messages of this level are shown.
+    pub level: Option<MessageLevel>,
+    /// If set, only messages sent by tasks of this name are shown.
+    pub origin: Option<String>,
+}
+
+/// All levels in the order they are cycled through.
+const LEVELS: &[MessageLevel] = &[
+    MessageLevel::Debug,
+    MessageLevel::Info,
+    MessageLevel::Success,
+    MessageLevel::Warning,
+    MessageLevel::Failure,
+];
+
+impl MessageFilter {
+    /// Returns true if messages may be hidden, or if the filter is being edited.
+    pub fn is_active(&self) -> bool {
+        self.is_editing || !self.text.is_empty() || self.level.is_some() || self.origin.is_some()
+    }
+
+    /// Show only messages of the next level, or all messages after the last level.
+    pub fn cycle_level(&mut self) {
+        self.level = match self.level {
+            None => LEVELS.first().copied(),
+            Some(level) => LEVELS
+                .iter()
+                .position(|&l| l == level)
+                .and_then(|index| LEVELS.get(index + 1))
+                .copied(),
+        };
+    }
+
+    /// Show only messages of the next origin in alphabetical order among the given `messages`, or all messages after
+    /// the last origin.
+    pub fn cycle_origin(&mut self, messages: &[Message]) {
+        let origins: BTreeSet<_> = messages.iter().map(|m| m.origin.as_str()).collect();
+        self.origin = match &self.origin {
+            None => origins.iter().next(),
+            Some(current) => origins.iter().find(|&&origin| origin > current.as_str()),
+        }
+        .map(|&origin| origin.to_owned());
+    }
+
+    fn matches(&self, lowercase_text: &str, message: &Message) -> bool {
+        !matches!(self.level, Some(level) if level != message.level)
+            && !matches!(&self.origin, Some(origin) if origin != &message.origin)
+            && (message.message.to_lowercase().contains(lowercase_text)
+                || message
+                    .fields
+                    .iter()
+                    .any(|(_, value)| value.to_lowercase().contains(lowercase_text)))
+    }
+}
+
+/// Returns the `messages` matching the `filter`.
+pub fn visible_messages<'a>(filter: &MessageFilter, messages: &'a [Message]) -> Cow<'a, [Message]> {
+    if !filter.is_active() {
+        return Cow::Borrowed(messages);
+    }
+    let text = filter.text.to_lowercase();
+    Cow::Owned(
+        messages
+            .iter()
+            .filter(|message| filter.matches(&text, message))
+            .cloned()
+            .collect(),
+    )
+}
+
+fn title(filter: &MessageFilter, shown: usize, total: usize) -> String {
+    if !filter.is_active() {
+        return "Messages".into();
+    }
+    let mut title = String::from("Messages");
+    if let Some(level) = filter.level {
+        title.push_str(&format!(" │ {} only", level.short_name()));
+    }
+    if let Some(origin) = &filter.origin {
+        title.push_str(&format!(" │ from {}", origin));
+    }
+    if filter.is_editing {
+        title.push_str(&format!(" │ search: {}▏", filter.text));
+    } else if !filter.text.is_empty() {
+        title.push_str(&format!(" │ search: {}", filter.text));
+    }
+    title.push_str(&format!(" │ {} of {} shown ", shown, total));
+    title
+}
+
 pub fn pane(
     messages: &[Message],
+    state: &mut State,
     bound: Rect,
     overflow_bound: Rect,
-    offset: &mut u16,
     buf: &mut Buffer,
 ) {
-    let mut block = Block::default().title("Messages").borders(Borders::TOP);
+    let State {
+        message_filter: filter,
+        message_offset: offset,
+        key_map,
+        theme,
+        ..
+    } = state;
+    let total = messages.len();
+    let messages = visible_messages(filter, messages);
+    let messages = messages.as_ref();
+    let title = title(filter, messages.len(), total);
+    let mut block = Block::default().title(&title).borders(Borders::TOP);
     block.draw(bound, buf);
-    let help_text = " ⨯ = `| ▢ = ~ ";
+    let help_text = format!(
+        " {} ",
+        key_map
+            .hints(&[
+                (Action::ToggleMessages, "⨯"),
+                (Action::ToggleMessagesFullscreen, "▢"),
+            ])
+            .join("| ")
+    );
     draw_text_nowrap(
-        rect::snap_to_right(bound, block_width(help_text)),
+        rect::snap_to_right(bound, block_width(&help_text)),
         buf,
         help_text,
         None,
@@ -47,6 +166,7 @@ pub fn pane(
             message,
             level,
             origin,
+            fields,
         },
     ) in messages
         .iter()
@@ -65,8 +185,8 @@ pub fn pane(
             draw_text_nowrap(
                 level_bound,
                 buf,
-                format_level_column(*level),
-                Some(level_to_style(*level)),
+                level.short_name(),
+                theme.message_level(*level),
             );
             draw_text_nowrap(
                 rect::offset_x(level_bound, LEVEL_TEXT_WIDTH),
@@ -84,7 +204,15 @@ pub fn pane(
                 None,
             );
         }
-        draw_text_nowrap(message_bound, buf, message, None);
+        let message_width = draw_text_nowrap(message_bound, buf, message, None);
+        if !fields.is_empty() {
+            draw_text_nowrap(
+                rect::offset_x(message_bound, message_width + 1),
+                buf,
+                FieldsDisplay(fields).to_string(),
+                theme.message_fields,
+            );
+        }
     }
 
     if (bound.height as usize) < messages.len().saturating_sub(*offset as usize)
@@ -100,9 +228,19 @@ pub fn pane(
             format!("… {} skipped and {} more", messages_skipped, messages_below),
             None,
         );
-        let help_text = " ⇊ = D|↓ = J|⇈ = U|↑ = K ┘";
+        let help_text = format!(
+            " {} ┘",
+            key_map
+                .hints(&[
+                    (Action::ScrollMessagesDownPage, "⇊"),
+                    (Action::ScrollMessagesDown, "↓"),
+                    (Action::ScrollMessagesUpPage, "⇈"),
+                    (Action::ScrollMessagesUp, "↑"),
+                ])
+                .join("|")
+        );
         draw_text_nowrap(
-            rect::snap_to_right(overflow_bound, block_width(help_text)),
+            rect::snap_to_right(overflow_bound, block_width(&help_text)),
             buf,
             help_text,
             None,
@@ -111,26 +249,6 @@ pub fn pane(
 }
 
 const LEVEL_TEXT_WIDTH: u16 = 4;
-fn format_level_column(level: MessageLevel) -> &'static str {
-    use MessageLevel::*;
-    match level {
-        Info => "info",
-        Failure => "fail",
-        Success => "done",
-    }
-}
-
-fn level_to_style(level: MessageLevel) -> Style {
-    use MessageLevel::*;
-    Style::default()
-        .fg(Color::Black)
-        .modifier(Modifier::BOLD)
-        .bg(match level {
-            Info => Color::White,
-            Failure => Color::Red,
-            Success => Color::Green,
-        })
-}
 
 fn format_time_column(time: &SystemTime) -> String {
     format!(
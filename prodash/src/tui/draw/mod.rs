@@ -1,6 +1,11 @@
 mod all;
+pub(crate) mod filter;
+mod help;
 mod information;
-mod messages;
+pub(crate) mod messages;
 mod progress;
+mod summary;
 
 pub use all::{all, State};
+pub use filter::TaskFilter;
+pub use messages::MessageFilter;
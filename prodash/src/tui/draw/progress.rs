@@ -1,25 +1,37 @@
 use crate::{
     tree::{Key, Progress, ProgressState, ProgressStep, Value},
-    tui::utils::{
-        block_width, draw_text_nowrap, draw_text_nowrap_fn, rect, sanitize_offset,
-        GraphemeCountWriter,
+    tui::{
+        utils::{
+            block_width, draw_text_nowrap, draw_text_nowrap_fn, fill_style, rect, sanitize_offset,
+            GraphemeCountWriter,
+        },
+        Action, KeyMap, Theme,
     },
+    unit::{Unit, UnitDisplay},
 };
 use humantime::format_duration;
 use std::{
     fmt,
+    ops::Range,
     time::{Duration, SystemTime},
 };
 use tui::{
     buffer::Buffer,
     layout::Rect,
-    style::{Color, Style},
+    style::{Modifier, Style},
 };
-use tui_react::fill_background;
 
 const MIN_TREE_WIDTH: u16 = 20;
 
-pub fn pane(entries: &[(Key, Value)], mut bound: Rect, offset: &mut u16, buf: &mut Buffer) {
+pub fn pane(
+    entries: &[(Key, Value)],
+    mut bound: Rect,
+    offset: &mut u16,
+    selected: Option<&Key>,
+    key_map: &KeyMap,
+    theme: &Theme,
+    buf: &mut Buffer,
+) {
     *offset = sanitize_offset(*offset, entries.len(), bound.height);
     let needs_overflow_line =
         if entries.len() > bound.height as usize || (*offset).min(entries.len() as u16) > 0 {
@@ -55,6 +67,28 @@ pub fn pane(entries: &[(Key, Value)], mut bound: Rect, offset: &mut u16, buf: &m
                     true
                 },
                 *offset,
+                theme,
+            );
+        }
+
+        if let Some(line) = entries
+            .iter()
+            .skip(*offset as usize)
+            .take(bound.height as usize)
+            .position(|(key, _)| Some(key) == selected)
+        {
+            let line_bound = rect::line_bound(bound, line);
+            highlight(
+                if max_tree_draw_width == 0 {
+                    line_bound
+                } else {
+                    Rect {
+                        width: max_tree_draw_width,
+                        ..line_bound
+                    }
+                },
+                theme.selection,
+                buf,
             );
         }
 
@@ -69,32 +103,46 @@ pub fn pane(entries: &[(Key, Value)], mut bound: Rect, offset: &mut u16, buf: &m
                 buf,
                 overflow_rect,
                 max_tree_draw_width,
-                bound.height,
-                *offset,
+                *offset..*offset + bound.height,
+                key_map,
+                theme,
             );
         }
     }
 }
 
+fn highlight(bound: Rect, modifier: Modifier, buf: &mut Buffer) {
+    for x in bound.left()..bound.right() {
+        buf.get_mut(x, bound.y).style.modifier.insert(modifier);
+    }
+}
+
 pub fn headline(
     entries: &[(Key, Value)],
     duration_per_frame: Duration,
     buf: &mut Buffer,
     bound: Rect,
 ) {
-    let (num_running_tasks, num_blocked_tasks, num_groups) = entries.iter().fold(
-        (0, 0, 0),
-        |(mut running, mut blocked, mut groups), (_key, Value { progress, .. })| {
-            match progress.map(|p| p.state) {
-                Some(ProgressState::Running) => running += 1,
-                Some(ProgressState::Blocked(_)) => blocked += 1,
-                None => groups += 1,
+    let (num_running_tasks, num_blocked_tasks, num_groups, num_ended_tasks) = entries.iter().fold(
+        (0, 0, 0, 0),
+        |(mut running, mut blocked, mut groups, mut ended),
+         (
+            _key,
+            Value {
+                progress, outcome, ..
+            },
+        )| {
+            match (progress.as_ref().map(|p| p.state), outcome) {
+                (_, Some(_)) => ended += 1,
+                (Some(ProgressState::Running), None) => running += 1,
+                (Some(ProgressState::Blocked(_)), None) => blocked += 1,
+                (None, None) => groups += 1,
             }
-            (running, blocked, groups)
+            (running, blocked, groups, ended)
         },
     );
     let text = format!(
-        "{} {:3} running + {:3} blocked + {:3} groups = {} ",
+        "{} {:3} running + {:3} blocked + {:3} groups{} = {} ",
         if duration_per_frame > Duration::from_secs(1) {
             format!(
                 " Every {}s → {}",
@@ -111,6 +159,11 @@ pub fn headline(
         num_running_tasks,
         num_blocked_tasks,
         num_groups,
+        if num_ended_tasks == 0 {
+            "".into()
+        } else {
+            format!(" + {:3} ended", num_ended_tasks)
+        },
         entries.len()
     );
     draw_text_nowrap(
@@ -127,12 +180,36 @@ impl<'a> fmt::Display for ProgressFormat<'a> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self.0 {
             Some(p) => {
-                match p.done_at {
-                    Some(done_at) => write!(f, "{} / {}", p.step, done_at),
-                    None => write!(f, "{}", p.step),
-                }?;
-                if let Some(unit) = p.unit {
-                    write!(f, " {}", unit)?;
+                write!(
+                    f,
+                    "{}",
+                    UnitDisplay::new(p.unit.as_ref(), p.step, p.done_at)
+                )?;
+                if let Some(throughput) = p.throughput.filter(|t| t.steps > 0) {
+                    let per_second = throughput.per_second();
+                    match p.unit {
+                        None | Some(Unit::Label(_)) if per_second < 10.0 => {
+                            write!(f, " @ {:.1}/s", per_second)
+                        }
+                        None | Some(Unit::Label(_)) => write!(f, " @ {:.0}/s", per_second),
+                        // seconds per second are better read as a factor
+                        Some(Unit::Duration) => write!(f, " @ {:.1}x", per_second),
+                        Some(ref unit) => write!(
+                            f,
+                            " @ {}/s",
+                            unit.display(per_second.round() as ProgressStep, None)
+                        ),
+                    }?;
+                    if let Some(time_left) = throughput
+                        .eta
+                        .and_then(|eta| eta.duration_since(SystemTime::now()).ok())
+                    {
+                        write!(
+                            f,
+                            ", {} left",
+                            format_duration(Duration::from_secs(time_left.as_secs()))
+                        )?;
+                    }
                 }
                 Ok(())
             }
@@ -147,6 +224,7 @@ pub fn draw_progress(
     bound: Rect,
     draw_column_line: bool,
     offset: u16,
+    theme: &Theme,
 ) {
     let title_spacing = 2u16 + 1; // 2 on the left, 1 on the right
     let column_line_width = if draw_column_line { 1 } else { 0 };
@@ -176,6 +254,7 @@ pub fn draw_progress(
                 Value {
                     progress,
                     name: title,
+                    ..
                 },
             )| match progress {
                 None => state.max(block_width(title) + key.level() as u16 + title_spacing),
@@ -190,6 +269,7 @@ pub fn draw_progress(
             Value {
                 progress,
                 name: title,
+                outcome,
             },
         ),
     ) in entries
@@ -199,6 +279,20 @@ pub fn draw_progress(
         .enumerate()
     {
         let line_bound = rect::line_bound(bound, line);
+        if let (Some(outcome), Some(progress)) = (outcome, progress) {
+            draw_text_nowrap(line_bound, buf, rect::VERTICAL_LINE, None);
+            draw_text_nowrap(
+                rect::offset_x(line_bound, column_line_width),
+                buf,
+                format!(
+                    " {} │ {}",
+                    outcome,
+                    UnitDisplay::new(progress.unit.as_ref(), progress.step, progress.done_at)
+                ),
+                theme.outcome(*outcome),
+            );
+            continue;
+        }
         let progress_text = format!(
             " {progress}",
             progress = ProgressFormat(progress, bound.width.saturating_sub(title_spacing))
@@ -207,7 +301,7 @@ pub fn draw_progress(
         draw_text_nowrap(line_bound, buf, rect::VERTICAL_LINE, None);
 
         let progress_rect = rect::offset_x(line_bound, column_line_width);
-        match progress.map(|p| (p.fraction(), p.state, p.step)) {
+        match progress.as_ref().map(|p| (p.fraction(), p.state, p.step)) {
             Some((Some(fraction), state, _step)) => {
                 let mut progress_text = progress_text;
                 if let ProgressState::Blocked(Some(eta)) = state {
@@ -222,12 +316,12 @@ pub fn draw_progress(
                 let (bound, style) =
                     draw_progress_bar_fn(buf, progress_rect, fraction, |fraction| {
                         if let ProgressState::Blocked(_) = state {
-                            return Color::Red;
+                            return theme.progress_bar_blocked;
                         }
                         if fraction >= 0.8 {
-                            Color::Green
+                            theme.progress_bar_nearly_done
                         } else {
-                            Color::Yellow
+                            theme.progress_bar
                         }
                     });
                 let style_fn = move |_t: &str, x: u16, _y: u16| {
@@ -248,9 +342,9 @@ pub fn draw_progress(
                     step,
                     line,
                     if let ProgressState::Blocked(_) = state {
-                        Color::Red
+                        theme.spinner_blocked
                     } else {
-                        Color::White
+                        theme.spinner
                     },
                 );
             }
@@ -269,7 +363,7 @@ pub fn draw_progress(
                     " {:‧<prefix_count$} {} ",
                     "",
                     title,
-                    prefix_count = key.level() as usize
+                    prefix_count = key.level()
                 );
                 draw_text_nowrap(progress_rect, buf, progress_text, None);
                 draw_text_nowrap(center_rect, buf, title_text, None);
@@ -278,7 +372,7 @@ pub fn draw_progress(
     }
 }
 
-fn draw_spinner(buf: &mut Buffer, bound: Rect, step: ProgressStep, seed: usize, color: Color) {
+fn draw_spinner(buf: &mut Buffer, bound: Rect, step: ProgressStep, seed: usize, style: Style) {
     if bound.width == 0 {
         return;
     }
@@ -286,14 +380,14 @@ fn draw_spinner(buf: &mut Buffer, bound: Rect, step: ProgressStep, seed: usize,
     let x = bound.x + ((step + seed) % bound.width as usize) as u16;
     let width = 5;
     let bound = rect::intersect(Rect { x, width, ..bound }, bound);
-    tui_react::fill_background(bound, buf, color);
+    fill_style(bound, buf, style);
 }
 
 fn draw_progress_bar_fn(
     buf: &mut Buffer,
     bound: Rect,
     fraction: f32,
-    style: impl FnOnce(f32) -> Color,
+    style: impl FnOnce(f32) -> Style,
 ) -> (Rect, Style) {
     if bound.width == 0 {
         return (Rect::default(), Style::default());
@@ -302,12 +396,9 @@ fn draw_progress_bar_fn(
         width: ((bound.width as f32 * fraction).ceil() as u16).min(bound.width),
         ..bound
     };
-    let color = style(fraction);
-    tui_react::fill_background(fractional_progress_rect, buf, color);
-    (
-        fractional_progress_rect,
-        Style::default().bg(color).fg(Color::Black),
-    )
+    let style = style(fraction);
+    fill_style(fractional_progress_rect, buf, style);
+    (fractional_progress_rect, style)
 }
 
 pub fn draw_tree(entries: &[(Key, Value)], buf: &mut Buffer, bound: Rect, offset: u16) -> u16 {
@@ -319,6 +410,7 @@ pub fn draw_tree(entries: &[(Key, Value)], buf: &mut Buffer, bound: Rect, offset
             Value {
                 progress,
                 name: title,
+                ..
             },
         ),
     ) in entries
@@ -340,7 +432,7 @@ pub fn draw_tree(entries: &[(Key, Value)], buf: &mut Buffer, bound: Rect, offset
                 }
             },
             if progress.is_none() { "" } else { &title },
-            width = key.level() as usize
+            width = key.level()
         );
         max_prefix_len = max_prefix_len.max(draw_text_nowrap(line_bound, buf, tree_prefix, None));
     }
@@ -352,22 +444,20 @@ pub fn draw_overflow<'a>(
     buf: &mut Buffer,
     bound: Rect,
     label_offset: u16,
-    num_entries_on_display: u16,
-    offset: u16,
+    shown: Range<u16>,
+    key_map: &KeyMap,
+    theme: &Theme,
 ) {
     let (count, mut progress_fraction) = entries
         .iter()
-        .take(offset as usize)
-        .chain(
-            entries
-                .iter()
-                .skip((offset + num_entries_on_display) as usize),
-        )
+        .take(shown.start as usize)
+        .chain(entries.iter().skip(shown.end as usize))
         .fold(
             (0usize, 0f32),
             |(count, progress_fraction), (_key, value)| {
                 let progress = value
                     .progress
+                    .as_ref()
                     .and_then(|p| p.fraction())
                     .unwrap_or_default();
                 (count + 1, progress_fraction + progress)
@@ -381,25 +471,23 @@ pub fn draw_overflow<'a>(
         } else {
             rect::VERTICAL_LINE
         },
-        offset,
-        entries
-            .len()
-            .saturating_sub((offset + num_entries_on_display + 1) as usize)
+        shown.start,
+        entries.len().saturating_sub(shown.end as usize + 1)
     );
     let (progress_rect, style) =
-        draw_progress_bar_fn(buf, bound, progress_fraction, |_| Color::Green);
+        draw_progress_bar_fn(buf, bound, progress_fraction, |_| theme.overflow_bar);
 
-    let bg_color = Color::Red;
-    fill_background(
+    let remainder_style = theme.overflow_remainder;
+    fill_style(
         rect::offset_x(bound, progress_rect.right() - 1),
         buf,
-        bg_color,
+        remainder_style,
     );
     let color_text_according_to_progress = move |_g: &str, x: u16, _y: u16| {
         if x < progress_rect.right() {
             style
         } else {
-            style.bg(bg_color)
+            remainder_style
         }
     };
     draw_text_nowrap_fn(
@@ -408,11 +496,21 @@ pub fn draw_overflow<'a>(
         label,
         color_text_according_to_progress,
     );
-    let help_text = "⇊ = d|↓ = j|⇈ = u|↑ = k ";
+    let help_text = format!(
+        "{} ",
+        key_map
+            .hints(&[
+                (Action::ScrollTasksDownPage, "⇊"),
+                (Action::ScrollTasksDown, "↓"),
+                (Action::ScrollTasksUpPage, "⇈"),
+                (Action::ScrollTasksUp, "↑"),
+            ])
+            .join("|")
+    );
     draw_text_nowrap_fn(
-        rect::snap_to_right(bound, block_width(help_text)),
+        rect::snap_to_right(bound, block_width(&help_text)),
         buf,
-        help_text,
+        &help_text,
         color_text_according_to_progress,
     );
 }
@@ -0,0 +1,94 @@
+use crate::{
+    tree::{Key, Outcome, Value},
+    tui::{
+        utils::{draw_text_nowrap, draw_text_nowrap_fn, fill_style, rect},
+        Theme,
+    },
+};
+use humantime::format_duration;
+use std::time::{Duration, SystemTime};
+use tui::{buffer::Buffer, layout::Rect, style::Style};
+
+/// The amount of lines taken by the summary pane.
+pub const HEIGHT: u16 = 2;
+
+/// Draw a bar showing the combined progress of all tasks with a known amount of work, along with the time elapsed
+/// since `started_at`, the amount of succeeded and failed tasks and the time at which all tasks are expected to be done.
+///
+/// Tasks are counted by their `Outcome`, which is only known while they are retained after they finished.
+pub fn pane(
+    entries: &[(Key, Value)],
+    started_at: Option<SystemTime>,
+    theme: &Theme,
+    bound: Rect,
+    buf: &mut Buffer,
+) {
+    if bound.height < HEIGHT {
+        return;
+    }
+    let now = SystemTime::now();
+    let (succeeded, failed) = entries
+        .iter()
+        .fold((0, 0), |(succeeded, failed), (_, value)| {
+            match value.outcome {
+                Some(Outcome::Succeeded) => (succeeded + 1, failed),
+                Some(Outcome::Failed) => (succeeded, failed + 1),
+                Some(Outcome::Cancelled) | None => (succeeded, failed),
+            }
+        });
+    let (num_bounded_tasks, fraction_sum, eta) = entries
+        .iter()
+        .filter_map(|(_, value)| value.progress.as_ref())
+        .fold((0usize, 0f32, None), |(count, sum, eta), progress| {
+            let eta = match (eta, progress.throughput.and_then(|t| t.eta)) {
+                (Some(eta), Some(task_eta)) => Some(task_eta.max(eta)),
+                (eta, task_eta) => eta.or(task_eta),
+            };
+            match progress.fraction() {
+                Some(fraction) => (count + 1, sum + fraction, eta),
+                None => (count, sum, eta),
+            }
+        });
+    let fraction = if num_bounded_tasks == 0 {
+        0.0
+    } else {
+        fraction_sum / num_bounded_tasks as f32
+    };
+
+    let mut text = format!(
+        " {:.0}% of {} task{}",
+        fraction * 100.0,
+        num_bounded_tasks,
+        if num_bounded_tasks == 1 { "" } else { "s" }
+    );
+    if let Some(elapsed) = started_at.and_then(|t| now.duration_since(t).ok()) {
+        text.push_str(&format!(
+            " │ {} elapsed",
+            format_duration(Duration::from_secs(elapsed.as_secs()))
+        ));
+    }
+    text.push_str(&format!(" │ {} done │ {} failed", succeeded, failed));
+    if let Some(time_left) = eta.and_then(|eta| eta.duration_since(now).ok()) {
+        text.push_str(&format!(
+            " │ {} left",
+            format_duration(Duration::from_secs(time_left.as_secs()))
+        ));
+    }
+
+    let line_bound = rect::line_bound(bound, 0);
+    let bar_bound = Rect {
+        width: ((line_bound.width as f32 * fraction).ceil() as u16).min(line_bound.width),
+        ..line_bound
+    };
+    fill_style(bar_bound, buf, theme.summary_bar);
+    draw_text_nowrap_fn(line_bound, buf, text, |_g, x, _y| {
+        if x < bar_bound.right() {
+            theme.summary_bar
+        } else {
+            Style::default()
+        }
+    });
+
+    let separator = rect::line_bound(bound, 1);
+    draw_text_nowrap(separator, buf, "─".repeat(separator.width as usize), None);
+}
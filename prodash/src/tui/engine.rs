@@ -1,10 +1,11 @@
-use crate::{tree::Root, tui::draw, tui::ticker};
+use crate::{
+    tree::Root,
+    tui::{backend, draw, ticker, Dashboard, Key, KeyHandling, KeyMap, Theme},
+};
 
-use futures::{channel::mpsc, SinkExt, StreamExt};
-use std::{io, time::Duration};
-use termion::{event::Key, input::TermRead, raw::IntoRawMode, screen::AlternateScreen};
-use tui::{backend::TermionBackend, layout::Rect};
-use tui_react::Terminal;
+use futures::StreamExt;
+use std::{path::PathBuf, time::Duration};
+use tui::layout::Rect;
 
 /// Configure the terminal user interface
 #[derive(Clone)]
@@ -22,6 +23,18 @@ pub struct TuiOptions {
     ///
     /// If unset, it will be retrieved from the current terminal.
     pub window_size: Option<Rect>,
+    /// The file to append buffered messages to when exporting them with `w` by default.
+    ///
+    /// The first export appends all buffered messages, and each further one appends the messages sent since.
+    pub messages_export_path: PathBuf,
+    /// The keys triggering actions of the user interface, which are listed in a help overlay with `?` by default.
+    pub key_map: KeyMap,
+    /// The colors and modifiers to draw with, which default to a monochrome theme if `NO_COLOR` is set.
+    pub theme: Theme,
+    /// If true, a bar with the combined progress of all tasks, the elapsed time and the amount of finished and failed
+    /// tasks is shown above the tasks. Finished tasks are only counted while they are
+    /// [retained](../struct.TreeOptions.html#structfield.retention).
+    pub show_summary: bool,
 }
 
 impl Default for TuiOptions {
@@ -30,10 +43,20 @@ impl Default for TuiOptions {
             title: "Progress Dashboard".into(),
             frames_per_second: 10.0,
             window_size: None,
+            messages_export_path: "prodash-messages.log".into(),
+            key_map: KeyMap::default(),
+            theme: Theme::default(),
+            show_summary: false,
         }
     }
 }
 
+impl TuiOptions {
+    pub(crate) fn duration_per_frame(&self) -> Duration {
+        Duration::from_secs_f32(1.0 / self.frames_per_second)
+    }
+}
+
 /// A line as used in [`Event::SetInformation`](./enum.Event.html#variant.SetInformation)
 pub enum Line {
     /// Set a title with the given text
@@ -60,6 +83,21 @@ pub enum Event {
     SetInformation(Vec<Line>),
 }
 
+/// Extends the TUI with additional keys and state, as used by replays.
+pub(crate) trait Controller: Send {
+    /// Returns true if the `key`, which the TUI doesn't handle itself, was handled and a redraw is needed.
+    fn handle_key(&mut self, key: Key) -> bool;
+    /// Called right before each frame is drawn.
+    fn before_draw(&mut self, state: &mut draw::State);
+}
+
+impl Controller for () {
+    fn handle_key(&mut self, _key: Key) -> bool {
+        false
+    }
+    fn before_draw(&mut self, _state: &mut draw::State) {}
+}
+
 /// Returns a future that draws the terminal user interface indefinitely.
 ///
 /// * `progress` is the progress tree whose information to visualize.
@@ -73,38 +111,23 @@ pub fn render_with_input(
     options: TuiOptions,
     events: impl futures::Stream<Item = Event> + Send,
 ) -> Result<impl std::future::Future<Output = ()>, std::io::Error> {
-    let TuiOptions {
-        title,
-        frames_per_second,
-        window_size,
-    } = options;
-    let mut terminal = {
-        let stdout = io::stdout().into_raw_mode()?;
-        let stdout = AlternateScreen::from(stdout);
-        let backend = TermionBackend::new(stdout);
-        Terminal::new(backend)?
-    };
+    render_with_controller(progress, options, events, ())
+}
 
-    let duration_per_frame = Duration::from_secs_f32(1.0 / frames_per_second);
-    let (mut key_send, key_receive) = mpsc::channel::<Key>(1);
+pub(crate) fn render_with_controller(
+    progress: Root,
+    options: TuiOptions,
+    events: impl futures::Stream<Item = Event> + Send,
+    mut controller: impl Controller,
+) -> Result<impl std::future::Future<Output = ()>, std::io::Error> {
+    let mut terminal = backend::new_terminal()?;
 
-    // This brings blocking key-handling into the async world
-    std::thread::spawn(move || -> Result<(), io::Error> {
-        for key in io::stdin().keys() {
-            let key = key?;
-            futures::executor::block_on(key_send.send(key)).ok();
-        }
-        Ok(())
-    });
+    let duration_per_frame = options.duration_per_frame();
+    let window_size = options.window_size;
+    let key_receive = backend::key_input_stream();
 
     let render_fut = async move {
-        let mut state = draw::State {
-            title,
-            duration_per_frame,
-            ..draw::State::default()
-        };
-        let mut entries = Vec::with_capacity(progress.num_tasks());
-        let mut messages = Vec::with_capacity(progress.messages_capacity());
+        let mut dashboard = Dashboard::new(progress, options);
         let mut events = futures::stream::select_all(vec![
             ticker(duration_per_frame).map(|_| Event::Tick).boxed(),
             key_receive.map(|key| Event::Input(key)).boxed(),
@@ -115,43 +138,27 @@ pub fn render_with_input(
             let mut skip_redraw = false;
             match event {
                 Event::Tick => {}
-                Event::Input(key) => match key {
-                    Key::Esc | Key::Char('q') | Key::Ctrl('c') | Key::Ctrl('[') => {
-                        break;
-                    }
-                    Key::Char('`') => state.hide_messages = !state.hide_messages,
-                    Key::Char('~') => state.messages_fullscreen = !state.messages_fullscreen,
-                    Key::Char('J') => state.message_offset = state.message_offset.saturating_add(1),
-                    Key::Char('D') => {
-                        state.message_offset = state.message_offset.saturating_add(10)
-                    }
-                    Key::Char('j') => state.task_offset = state.task_offset.saturating_add(1),
-                    Key::Char('d') => state.task_offset = state.task_offset.saturating_add(10),
-                    Key::Char('K') => state.message_offset = state.message_offset.saturating_sub(1),
-                    Key::Char('U') => {
-                        state.message_offset = state.message_offset.saturating_sub(10)
-                    }
-                    Key::Char('k') => state.task_offset = state.task_offset.saturating_sub(1),
-                    Key::Char('u') => state.task_offset = state.task_offset.saturating_sub(10),
-                    Key::Char('[') => state.hide_info = !state.hide_info,
-                    Key::Char('{') => state.maximize_info = !state.maximize_info,
-                    _ => skip_redraw = true,
+                Event::Input(key) => match dashboard.handle_key(key) {
+                    KeyHandling::Handled => {}
+                    KeyHandling::Quit => break,
+                    KeyHandling::Ignored => skip_redraw = !controller.handle_key(key),
                 },
-                Event::SetWindowSize(bound) => state.user_provided_window_size = Some(bound),
-                Event::SetTitle(title) => state.title = title,
-                Event::SetInformation(info) => state.information = info,
+                Event::SetWindowSize(bound) => {
+                    dashboard.state_mut().user_provided_window_size = Some(bound)
+                }
+                Event::SetTitle(title) => dashboard.state_mut().title = title,
+                Event::SetInformation(info) => dashboard.state_mut().information = info,
             }
             if !skip_redraw {
                 let terminal_window_size = terminal.pre_render().expect("pre-render to work");
-                let window_size = state
+                let window_size = dashboard
+                    .state()
                     .user_provided_window_size
                     .or(window_size)
                     .unwrap_or(terminal_window_size);
                 let buf = terminal.current_buffer_mut();
-                progress.sorted_snapshot(&mut entries);
-                progress.copy_messages(&mut messages);
-
-                draw::all(&mut state, &entries, &messages, window_size, buf);
+                controller.before_draw(dashboard.state_mut());
+                dashboard.draw(window_size, buf);
                 terminal.post_render().expect("post render to work");
             }
         }
@@ -0,0 +1,74 @@
+use crate::{
+    tree::{Key, Message, Value},
+    tui::draw,
+};
+use tui::{buffer::Buffer, layout::Rect};
+use unicode_width::UnicodeWidthStr;
+
+/// Draw a snapshot of a progress tree into a new buffer covering `bound`, exactly like the terminal user interface
+/// would, but without needing a terminal.
+///
+/// `entries` and `messages` are obtained with [`Root::sorted_snapshot(…)`](../tree/struct.Root.html#method.sorted_snapshot)
+/// and [`Root::copy_messages(…)`](../tree/struct.Root.html#method.copy_messages) respectively. The `state` may be altered
+/// while drawing, for instance to keep offsets within bounds.
+///
+/// ```rust
+/// use prodash::tui::{self, tui_export::layout::Rect};
+/// let root = prodash::Tree::new();
+/// let mut progress = root.add_child("task");
+/// progress.init(Some(10), None);
+/// progress.set(5);
+///
+/// let (mut entries, mut messages) = (Vec::new(), Vec::new());
+/// root.sorted_snapshot(&mut entries);
+/// root.copy_messages(&mut messages);
+/// let mut state = tui::State { title: "frame".into(), ..tui::State::default() };
+/// let frame = tui::render_to_string(&mut state, &entries, &messages, Rect::new(0, 0, 60, 4));
+/// assert!(frame.contains("task"));
+/// assert!(frame.contains("5 / 10"));
+/// ```
+pub fn render_to_buffer(
+    state: &mut draw::State,
+    entries: &[(Key, Value)],
+    messages: &[Message],
+    bound: Rect,
+) -> Buffer {
+    let mut buf = Buffer::empty(bound);
+    draw::all(state, entries, messages, bound, &mut buf);
+    buf
+}
+
+/// Like [`render_to_buffer(…)`](./fn.render_to_buffer.html), but returns the drawn symbols as string as
+/// [`buffer_to_string(…)`](./fn.buffer_to_string.html) would.
+pub fn render_to_string(
+    state: &mut draw::State,
+    entries: &[(Key, Value)],
+    messages: &[Message],
+    bound: Rect,
+) -> String {
+    buffer_to_string(&render_to_buffer(state, entries, messages, bound))
+}
+
+/// Returns the symbols in `buf` line by line, without trailing whitespace and without any styles.
+///
+/// Wide characters occupy more than one cell, and the cells they cover are skipped.
+pub fn buffer_to_string(buf: &Buffer) -> String {
+    let area = buf.area();
+    let mut out = String::with_capacity(area.area() as usize);
+    for y in area.top()..area.bottom() {
+        let mut line = String::with_capacity(area.width as usize);
+        let mut cells_to_skip = 0;
+        for x in area.left()..area.right() {
+            if cells_to_skip > 0 {
+                cells_to_skip -= 1;
+                continue;
+            }
+            let symbol = &buf.get(x, y).symbol;
+            line.push_str(symbol);
+            cells_to_skip = symbol.width().saturating_sub(1);
+        }
+        out.push_str(line.trim_end());
+        out.push('\n');
+    }
+    out
+}
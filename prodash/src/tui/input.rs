@@ -0,0 +1,130 @@
+use std::fmt;
+
+/// A key pressed by the user, independent of the terminal backend, as used in
+/// [`Event::Input`](./enum.Event.html#variant.Input).
+///
+/// The enter key is represented as `Char('\n')`, and the tab key as `Char('\t')`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Key {
+    /// Backspace
+    Backspace,
+    /// Left arrow
+    Left,
+    /// Right arrow
+    Right,
+    /// Up arrow
+    Up,
+    /// Down arrow
+    Down,
+    /// Home key
+    Home,
+    /// End key
+    End,
+    /// Page Up
+    PageUp,
+    /// Page Down
+    PageDown,
+    /// Backward Tab key
+    BackTab,
+    /// Delete key
+    Delete,
+    /// Insert key
+    Insert,
+    /// Function keys, with `F(1)` being F1
+    F(u8),
+    /// A normal character
+    Char(char),
+    /// A character pressed along with Alt
+    Alt(char),
+    /// A character pressed along with Ctrl
+    Ctrl(char),
+    /// Null byte, also used for keys which are not known to prodash
+    Null,
+    /// Escape key
+    Esc,
+}
+
+#[cfg(feature = "tui-renderer-termion")]
+impl From<termion::event::Key> for Key {
+    fn from(key: termion::event::Key) -> Self {
+        use termion::event::Key as K;
+        match key {
+            K::Backspace => Key::Backspace,
+            K::Left => Key::Left,
+            K::Right => Key::Right,
+            K::Up => Key::Up,
+            K::Down => Key::Down,
+            K::Home => Key::Home,
+            K::End => Key::End,
+            K::PageUp => Key::PageUp,
+            K::PageDown => Key::PageDown,
+            K::BackTab => Key::BackTab,
+            K::Delete => Key::Delete,
+            K::Insert => Key::Insert,
+            K::F(n) => Key::F(n),
+            K::Char(c) => Key::Char(c),
+            K::Alt(c) => Key::Alt(c),
+            K::Ctrl(c) => Key::Ctrl(c),
+            K::Esc => Key::Esc,
+            _ => Key::Null,
+        }
+    }
+}
+
+#[cfg(feature = "tui-renderer-crossterm")]
+impl From<crossterm::event::KeyEvent> for Key {
+    fn from(key: crossterm::event::KeyEvent) -> Self {
+        use crossterm::event::{KeyCode as K, KeyModifiers};
+        match key.code {
+            K::Backspace => Key::Backspace,
+            K::Enter => Key::Char('\n'),
+            K::Left => Key::Left,
+            K::Right => Key::Right,
+            K::Up => Key::Up,
+            K::Down => Key::Down,
+            K::Home => Key::Home,
+            K::End => Key::End,
+            K::PageUp => Key::PageUp,
+            K::PageDown => Key::PageDown,
+            K::Tab => Key::Char('\t'),
+            K::BackTab => Key::BackTab,
+            K::Delete => Key::Delete,
+            K::Insert => Key::Insert,
+            K::F(n) => Key::F(n),
+            K::Char(c) if key.modifiers.contains(KeyModifiers::CONTROL) => Key::Ctrl(c),
+            K::Char(c) if key.modifiers.contains(KeyModifiers::ALT) => Key::Alt(c),
+            K::Char(c) => Key::Char(c),
+            K::Null => Key::Null,
+            K::Esc => Key::Esc,
+        }
+    }
+}
+
+/// Displays the key as shown in the help overlay, like `ctrl-c` or `↑`.
+impl fmt::Display for Key {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Key::Backspace => f.write_str("backspace"),
+            Key::Left => f.write_str("←"),
+            Key::Right => f.write_str("→"),
+            Key::Up => f.write_str("↑"),
+            Key::Down => f.write_str("↓"),
+            Key::Home => f.write_str("home"),
+            Key::End => f.write_str("end"),
+            Key::PageUp => f.write_str("page-up"),
+            Key::PageDown => f.write_str("page-down"),
+            Key::BackTab => f.write_str("shift-tab"),
+            Key::Delete => f.write_str("delete"),
+            Key::Insert => f.write_str("insert"),
+            Key::F(n) => write!(f, "F{}", n),
+            Key::Char(' ') => f.write_str("space"),
+            Key::Char('\n') => f.write_str("enter"),
+            Key::Char('\t') => f.write_str("tab"),
+            Key::Char(c) => write!(f, "{}", c),
+            Key::Alt(c) => write!(f, "alt-{}", c),
+            Key::Ctrl(c) => write!(f, "ctrl-{}", c),
+            Key::Null => f.write_str("null"),
+            Key::Esc => f.write_str("esc"),
+        }
+    }
+}
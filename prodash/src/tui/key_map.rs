@@ -0,0 +1,215 @@
+use crate::tui::Key;
+
+/// Something the user can do in the terminal user interface by pressing a key bound to it in a
+/// [`KeyMap`](./struct.KeyMap.html).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Action {
+    /// Stop the terminal user interface
+    Quit,
+    /// Show or hide the list of all key bindings
+    ToggleHelp,
+    /// Show or hide the messages pane
+    ToggleMessages,
+    /// Let the messages pane take most of the space, or share it with the tasks again
+    ToggleMessagesFullscreen,
+    /// Scroll the messages pane down by one message
+    ScrollMessagesDown,
+    /// Scroll the messages pane down by ten messages
+    ScrollMessagesDownPage,
+    /// Scroll the messages pane up by one message
+    ScrollMessagesUp,
+    /// Scroll the messages pane up by ten messages
+    ScrollMessagesUpPage,
+    /// Scroll the tasks pane down by one task
+    ScrollTasksDown,
+    /// Scroll the tasks pane down by ten tasks
+    ScrollTasksDownPage,
+    /// Scroll the tasks pane up by one task
+    ScrollTasksUp,
+    /// Scroll the tasks pane up by ten tasks
+    ScrollTasksUpPage,
+    /// Select the task below the selected one
+    SelectNextTask,
+    /// Select the task above the selected one
+    SelectPreviousTask,
+    /// Replace the descendants of the selected task with a summary line, or show them again
+    ToggleCollapsed,
+    /// Show only the selected task and its descendants, or all tasks again
+    ToggleFocus,
+    /// Start typing text to filter tasks by name
+    EditTaskFilter,
+    /// Hide or show finished tasks
+    ToggleHideFinished,
+    /// Hide or show tasks which don't make progress
+    ToggleHideIdle,
+    /// Hide or show blocked tasks
+    ToggleHideBlocked,
+    /// Start typing text to search messages
+    SearchMessages,
+    /// Show only messages of the next level
+    CycleMessageLevel,
+    /// Show only messages of the next origin
+    CycleMessageOrigin,
+    /// Write all buffered messages to a file
+    ExportMessages,
+    /// Show or hide the information pane
+    ToggleInformation,
+    /// Let the information pane take as much space as it needs
+    ToggleMaximizeInformation,
+    /// Show or hide the combined progress of all tasks
+    ToggleSummary,
+}
+
+impl Action {
+    /// Returns a short description of what the action does, as shown in the help overlay.
+    pub fn description(self) -> &'static str {
+        use Action::*;
+        match self {
+            Quit => "quit",
+            ToggleHelp => "show or hide this help",
+            ToggleMessages => "show or hide messages",
+            ToggleMessagesFullscreen => "maximize messages",
+            ScrollMessagesDown => "scroll messages down",
+            ScrollMessagesDownPage => "scroll messages down by ten",
+            ScrollMessagesUp => "scroll messages up",
+            ScrollMessagesUpPage => "scroll messages up by ten",
+            ScrollTasksDown => "scroll tasks down",
+            ScrollTasksDownPage => "scroll tasks down by ten",
+            ScrollTasksUp => "scroll tasks up",
+            ScrollTasksUpPage => "scroll tasks up by ten",
+            SelectNextTask => "select the next task",
+            SelectPreviousTask => "select the previous task",
+            ToggleCollapsed => "collapse or expand the selected task",
+            ToggleFocus => "focus on the selected task, or show all",
+            EditTaskFilter => "filter tasks by name",
+            ToggleHideFinished => "hide or show finished tasks",
+            ToggleHideIdle => "hide or show idle tasks",
+            ToggleHideBlocked => "hide or show blocked tasks",
+            SearchMessages => "search messages",
+            CycleMessageLevel => "show messages of the next level",
+            CycleMessageOrigin => "show messages of the next origin",
+            ExportMessages => "append new messages to a file",
+            ToggleInformation => "show or hide information",
+            ToggleMaximizeInformation => "maximize information",
+            ToggleSummary => "show or hide the overall progress",
+        }
+    }
+}
+
+/// Binds keys to [actions](./enum.Action.html) of the terminal user interface, as configured in
+/// [`TuiOptions`](./struct.TuiOptions.html).
+///
+/// Each key triggers at most one action, but an action may be triggered by any amount of keys.
+///
+/// ```rust
+/// use prodash::tui::{Action, Key, KeyMap};
+/// let mut keys = KeyMap::default();
+/// keys.bind(Key::Char('Q'), Action::Quit);
+/// keys.unbind(Key::Char('q'));
+/// assert_eq!(keys.action(Key::Char('Q')), Some(Action::Quit));
+/// assert_eq!(keys.action(Key::Char('q')), None);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeyMap {
+    bindings: Vec<(Key, Action)>,
+}
+
+impl KeyMap {
+    /// Returns a key map without any bindings.
+    pub fn empty() -> Self {
+        KeyMap {
+            bindings: Vec::new(),
+        }
+    }
+
+    /// Let `key` trigger `action`, replacing the action it triggered before, if any.
+    pub fn bind(&mut self, key: Key, action: Action) -> &mut Self {
+        match self.bindings.iter_mut().find(|(k, _)| *k == key) {
+            Some(binding) => binding.1 = action,
+            None => self.bindings.push((key, action)),
+        }
+        self
+    }
+
+    /// Let `key` trigger nothing.
+    pub fn unbind(&mut self, key: Key) -> &mut Self {
+        self.bindings.retain(|(k, _)| *k != key);
+        self
+    }
+
+    /// Returns the action triggered by `key`, if any.
+    pub fn action(&self, key: Key) -> Option<Action> {
+        self.bindings
+            .iter()
+            .find(|(k, _)| *k == key)
+            .map(|(_, action)| *action)
+    }
+
+    /// Returns all keys triggering `action`, in the order they were bound.
+    pub fn keys(&self, action: Action) -> impl Iterator<Item = Key> + '_ {
+        self.bindings
+            .iter()
+            .filter(move |(_, a)| *a == action)
+            .map(|(key, _)| *key)
+    }
+
+    /// Returns all bindings, in the order they were made.
+    pub fn bindings(&self) -> &[(Key, Action)] {
+        &self.bindings
+    }
+
+    /// Returns a hint like `⨯ = q` for each action which is bound to a key, using the given symbol for the action
+    /// and its first key.
+    pub(crate) fn hints(&self, actions: &[(Action, &str)]) -> Vec<String> {
+        actions
+            .iter()
+            .filter_map(|&(action, symbol)| {
+                self.keys(action)
+                    .next()
+                    .map(|key| format!("{} = {}", symbol, key))
+            })
+            .collect()
+    }
+}
+
+impl Default for KeyMap {
+    fn default() -> Self {
+        use Action::*;
+        let mut keys = KeyMap::empty();
+        for &(key, action) in &[
+            (Key::Esc, Quit),
+            (Key::Char('q'), Quit),
+            (Key::Ctrl('c'), Quit),
+            (Key::Ctrl('['), Quit),
+            (Key::Char('?'), ToggleHelp),
+            (Key::Char('`'), ToggleMessages),
+            (Key::Char('~'), ToggleMessagesFullscreen),
+            (Key::Char('J'), ScrollMessagesDown),
+            (Key::Char('D'), ScrollMessagesDownPage),
+            (Key::Char('K'), ScrollMessagesUp),
+            (Key::Char('U'), ScrollMessagesUpPage),
+            (Key::Char('j'), ScrollTasksDown),
+            (Key::Char('d'), ScrollTasksDownPage),
+            (Key::Char('k'), ScrollTasksUp),
+            (Key::Char('u'), ScrollTasksUpPage),
+            (Key::Down, SelectNextTask),
+            (Key::Up, SelectPreviousTask),
+            (Key::Char('\n'), ToggleCollapsed),
+            (Key::Char('z'), ToggleFocus),
+            (Key::Char('/'), EditTaskFilter),
+            (Key::Char('f'), ToggleHideFinished),
+            (Key::Char('i'), ToggleHideIdle),
+            (Key::Char('b'), ToggleHideBlocked),
+            (Key::Char('s'), SearchMessages),
+            (Key::Char('l'), CycleMessageLevel),
+            (Key::Char('o'), CycleMessageOrigin),
+            (Key::Char('w'), ExportMessages),
+            (Key::Char('['), ToggleInformation),
+            (Key::Char('{'), ToggleMaximizeInformation),
+            (Key::Char('S'), ToggleSummary),
+        ] {
+            keys.bind(key, action);
+        }
+        keys
+    }
+}
@@ -30,7 +30,7 @@
 *     use futures::StreamExt;
 *     let mut progress = root.add_child("task");
 *     async move {
-*         progress.init(None, Some("items"));
+*         progress.init(None, Some("items".into()));
 *         let mut count = 0;
 *         let  mut ticks = ticker(std::time::Duration::from_millis(100));
 *         while let Some(_) = ticks.next().await {
@@ -46,13 +46,30 @@
 * # Ok(())
 * # }
 * ```
+*
+* # Embedding
+*
+* Applications with a terminal user interface of their own can draw a [`Dashboard`](./struct.Dashboard.html) into
+* any part of their frames, and pass it the keys meant for it.
 */
-mod draw;
-mod engine;
+mod backend;
+pub(crate) mod draw;
+pub(crate) mod engine;
+mod headless;
+mod input;
+mod key_map;
+mod theme;
 mod utils;
+mod widget;
 
+pub use draw::{MessageFilter, State, TaskFilter};
 pub use engine::*;
+pub use headless::{buffer_to_string, render_to_buffer, render_to_string};
+pub use input::Key;
+pub use key_map::{Action, KeyMap};
+pub use theme::Theme;
 pub use utils::ticker;
+pub use widget::{Dashboard, KeyHandling};
 
 /// Useful for bringing up the TUI without bringing in the `tui` crate yourself
 pub use tui as tui_export;
@@ -0,0 +1,178 @@
+use crate::tree::{MessageLevel, Outcome};
+use tui::style::{Color, Modifier, Style};
+
+/// The colors and modifiers used to draw the terminal user interface, as configured in
+/// [`TuiOptions`](./struct.TuiOptions.html).
+///
+/// Styles are applied to the cells they are drawn on as a whole, replacing their foreground, background and modifiers.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Theme {
+    /// The bar of tasks which made less than 80% of their progress.
+    pub progress_bar: Style,
+    /// The bar of tasks which made at least 80% of their progress.
+    pub progress_bar_nearly_done: Style,
+    /// The bar of blocked tasks.
+    pub progress_bar_blocked: Style,
+    /// The spinner of tasks without a known amount of work.
+    pub spinner: Style,
+    /// The spinner of blocked tasks without a known amount of work.
+    pub spinner_blocked: Style,
+    /// The bar on the overflow line, showing the progress of all tasks scrolled out of view.
+    pub overflow_bar: Style,
+    /// The part of the overflow line which isn't covered by its bar.
+    pub overflow_remainder: Style,
+    /// The bar showing the combined progress of all tasks in the summary.
+    pub summary_bar: Style,
+    /// Tasks which succeeded and are kept in the tree for a while.
+    pub task_succeeded: Style,
+    /// Tasks which failed and are kept in the tree for a while.
+    pub task_failed: Style,
+    /// Tasks which were cancelled and are kept in the tree for a while.
+    pub task_cancelled: Style,
+    /// Added to the cells of the selected task.
+    pub selection: Modifier,
+    /// The line showing the task filter.
+    pub status_line: Style,
+    /// The level of debug messages.
+    pub message_debug: Style,
+    /// The level of informational messages.
+    pub message_info: Style,
+    /// The level of success messages.
+    pub message_success: Style,
+    /// The level of warnings.
+    pub message_warning: Style,
+    /// The level of failure messages.
+    pub message_failure: Style,
+    /// The key/value fields of messages.
+    pub message_fields: Style,
+    /// The keys in the help overlay.
+    pub help_key: Style,
+}
+
+impl Theme {
+    /// Colors for terminals with a dark background.
+    pub fn dark() -> Self {
+        let on = |color| Style::default().fg(Color::Black).bg(color);
+        let level = |color| on(color).modifier(Modifier::BOLD);
+        Theme {
+            progress_bar: on(Color::Yellow),
+            progress_bar_nearly_done: on(Color::Green),
+            progress_bar_blocked: on(Color::Red),
+            spinner: Style::default().bg(Color::White),
+            spinner_blocked: Style::default().bg(Color::Red),
+            overflow_bar: on(Color::Green),
+            overflow_remainder: on(Color::Red),
+            summary_bar: on(Color::Cyan),
+            task_succeeded: Style::default().fg(Color::Green),
+            task_failed: Style::default().fg(Color::Red),
+            task_cancelled: Style::default().fg(Color::DarkGray),
+            selection: Modifier::REVERSED,
+            status_line: Style::default().modifier(Modifier::REVERSED),
+            message_debug: level(Color::DarkGray),
+            message_info: level(Color::White),
+            message_success: level(Color::Green),
+            message_warning: level(Color::Yellow),
+            message_failure: level(Color::Red),
+            message_fields: Style::default().modifier(Modifier::DIM),
+            help_key: Style::default().modifier(Modifier::BOLD),
+        }
+    }
+
+    /// Colors for terminals with a light background, avoiding white and pale colors.
+    pub fn light() -> Self {
+        let on = |fg, bg| Style::default().fg(fg).bg(bg);
+        let level = |fg, bg| on(fg, bg).modifier(Modifier::BOLD);
+        Theme {
+            progress_bar: on(Color::Black, Color::Yellow),
+            progress_bar_nearly_done: on(Color::White, Color::Green),
+            progress_bar_blocked: on(Color::White, Color::Red),
+            spinner: Style::default().bg(Color::DarkGray),
+            spinner_blocked: Style::default().bg(Color::Red),
+            overflow_bar: on(Color::White, Color::Green),
+            overflow_remainder: on(Color::White, Color::Red),
+            summary_bar: on(Color::White, Color::Blue),
+            task_succeeded: Style::default().fg(Color::Green),
+            task_failed: Style::default().fg(Color::Red),
+            task_cancelled: Style::default().fg(Color::DarkGray),
+            selection: Modifier::REVERSED,
+            status_line: Style::default().modifier(Modifier::REVERSED),
+            message_debug: level(Color::White, Color::DarkGray),
+            message_info: level(Color::White, Color::Blue),
+            message_success: level(Color::White, Color::Green),
+            message_warning: level(Color::Black, Color::Yellow),
+            message_failure: level(Color::White, Color::Red),
+            message_fields: Style::default().fg(Color::DarkGray),
+            help_key: Style::default().fg(Color::Blue).modifier(Modifier::BOLD),
+        }
+    }
+
+    /// No colors at all, using modifiers like bold and reverse instead.
+    pub fn monochrome() -> Self {
+        let with = |modifier| Style::default().modifier(modifier);
+        Theme {
+            progress_bar: with(Modifier::REVERSED),
+            progress_bar_nearly_done: with(Modifier::REVERSED | Modifier::BOLD),
+            progress_bar_blocked: with(Modifier::REVERSED | Modifier::DIM),
+            spinner: with(Modifier::REVERSED),
+            spinner_blocked: with(Modifier::REVERSED | Modifier::DIM),
+            overflow_bar: with(Modifier::REVERSED),
+            overflow_remainder: Style::default(),
+            summary_bar: with(Modifier::REVERSED),
+            task_succeeded: with(Modifier::BOLD),
+            task_failed: with(Modifier::BOLD | Modifier::REVERSED),
+            task_cancelled: with(Modifier::DIM),
+            selection: Modifier::BOLD | Modifier::UNDERLINED,
+            status_line: with(Modifier::REVERSED),
+            message_debug: with(Modifier::DIM),
+            message_info: Style::default(),
+            message_success: with(Modifier::BOLD),
+            message_warning: with(Modifier::BOLD | Modifier::UNDERLINED),
+            message_failure: with(Modifier::BOLD | Modifier::REVERSED),
+            message_fields: with(Modifier::DIM),
+            help_key: with(Modifier::BOLD),
+        }
+    }
+
+    /// Returns the [monochrome](#method.monochrome) theme if the `NO_COLOR` environment variable is set to a non-empty
+    /// value, as suggested on [no-color.org](https://no-color.org), or the [dark](#method.dark) one otherwise.
+    ///
+    /// ```rust
+    /// use prodash::tui::Theme;
+    /// std::env::set_var("NO_COLOR", "1");
+    /// assert_eq!(Theme::from_env(), Theme::monochrome());
+    /// std::env::set_var("NO_COLOR", "");
+    /// assert_eq!(Theme::from_env(), Theme::dark());
+    /// ```
+    pub fn from_env() -> Self {
+        match std::env::var_os("NO_COLOR") {
+            Some(value) if !value.is_empty() => Theme::monochrome(),
+            _ => Theme::dark(),
+        }
+    }
+
+    pub(crate) fn outcome(&self, outcome: Outcome) -> Style {
+        match outcome {
+            Outcome::Succeeded => self.task_succeeded,
+            Outcome::Failed => self.task_failed,
+            Outcome::Cancelled => self.task_cancelled,
+        }
+    }
+
+    pub(crate) fn message_level(&self, level: MessageLevel) -> Style {
+        use MessageLevel::*;
+        match level {
+            Debug => self.message_debug,
+            Info => self.message_info,
+            Success => self.message_success,
+            Warning => self.message_warning,
+            Failure => self.message_failure,
+        }
+    }
+}
+
+/// Uses [`Theme::from_env()`](#method.from_env).
+impl Default for Theme {
+    fn default() -> Self {
+        Theme::from_env()
+    }
+}
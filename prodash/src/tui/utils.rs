@@ -86,6 +86,15 @@ pub fn draw_text_nowrap<'a>(
     total_width as u16
 }
 
+// TODO: put this in tui-react
+pub fn fill_style(bound: Rect, buf: &mut Buffer, style: Style) {
+    for y in bound.top()..bound.bottom() {
+        for x in bound.left()..bound.right() {
+            buf.get_mut(x, y).set_style(style);
+        }
+    }
+}
+
 // TODO: put this in tui-react
 pub fn draw_text_nowrap_fn(
     bound: Rect,
@@ -0,0 +1,311 @@
+use crate::{
+    tree::{self, Root},
+    tui::{draw, Action, Key, TuiOptions},
+};
+use std::{
+    fs,
+    io::{self, Write},
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+use tui::{buffer::Buffer, layout::Rect};
+
+/// What a [`Dashboard`](./struct.Dashboard.html) did with a key passed to
+/// [`Dashboard::handle_key(…)`](./struct.Dashboard.html#method.handle_key).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyHandling {
+    /// The key changed the state, and the dashboard should be drawn again.
+    Handled,
+    /// The key isn't used by the dashboard.
+    Ignored,
+    /// The user asked to quit, which the dashboard leaves to the caller.
+    Quit,
+}
+
+/// A widget to draw a progress tree into any buffer, for embedding it into terminal user interfaces which
+/// own the terminal and the input themselves.
+///
+/// It is what [`render_with_input(…)`](./fn.render_with_input.html) uses to draw, and responds to the keys of the
+/// [`KeyMap`](./struct.KeyMap.html) in its options.
+///
+/// ```rust
+/// use prodash::tui::{self, tui_export::{buffer::Buffer, layout::Rect}, Dashboard, Key, KeyHandling};
+/// let root = prodash::Tree::new();
+/// let mut progress = root.add_child("task");
+/// progress.init(Some(10), None);
+/// progress.set(5);
+///
+/// let mut dashboard = Dashboard::new(root, tui::TuiOptions::default());
+/// assert_eq!(dashboard.handle_key(Key::Char('f')), KeyHandling::Handled);
+/// assert_eq!(dashboard.handle_key(Key::Char('q')), KeyHandling::Quit);
+///
+/// let bound = Rect::new(0, 0, 60, 6);
+/// let mut buf = Buffer::empty(bound);
+/// dashboard.draw(bound, &mut buf);
+/// assert!(tui::buffer_to_string(&buf).contains("task"));
+/// ```
+pub struct Dashboard {
+    progress: Root,
+    state: draw::State,
+    entries: Vec<(tree::Key, tree::Value)>,
+    messages: Vec<tree::Message>,
+    messages_export_path: PathBuf,
+    /// The amount of messages sent before the last export, to only append new ones.
+    messages_exported: usize,
+}
+
+impl Dashboard {
+    /// Create a new instance to draw the given `progress` tree, configured with `options`.
+    ///
+    /// The `window_size` of the `options` is ignored, as the caller decides where to draw.
+    pub fn new(progress: Root, options: TuiOptions) -> Self {
+        let duration_per_frame = options.duration_per_frame();
+        let TuiOptions {
+            title,
+            messages_export_path,
+            key_map,
+            theme,
+            show_summary,
+            ..
+        } = options;
+        Dashboard {
+            entries: Vec::with_capacity(progress.num_tasks()),
+            messages: Vec::with_capacity(progress.messages_capacity()),
+            progress,
+            state: draw::State {
+                title,
+                duration_per_frame,
+                key_map,
+                theme,
+                show_summary,
+                started_at: Some(SystemTime::now()),
+                ..draw::State::default()
+            },
+            messages_export_path,
+            messages_exported: 0,
+        }
+    }
+
+    /// Returns the state of the user interface, as changed by keys.
+    pub fn state(&self) -> &draw::State {
+        &self.state
+    }
+
+    /// Returns the state of the user interface for changing it, for instance to set the information to display.
+    pub fn state_mut(&mut self) -> &mut draw::State {
+        &mut self.state
+    }
+
+    /// Take a snapshot of the progress tree and its messages, and draw it into `buf` within `bound`, replacing
+    /// whatever was drawn there before.
+    pub fn draw(&mut self, bound: Rect, buf: &mut Buffer) {
+        let bound = bound.intersection(*buf.area());
+        for y in bound.top()..bound.bottom() {
+            for x in bound.left()..bound.right() {
+                buf.get_mut(x, y).reset();
+            }
+        }
+        self.progress.sorted_snapshot(&mut self.entries);
+        self.progress.copy_messages(&mut self.messages);
+        draw::all(&mut self.state, &self.entries, &self.messages, bound, buf);
+    }
+
+    /// Change the state according to the action the `key` pressed by the user is bound to in the key map.
+    ///
+    /// While the help overlay is shown, any key hides it. While filter text is typed, keys are used for editing instead.
+    /// Tasks are selected among the ones shown when the dashboard was last drawn.
+    pub fn handle_key(&mut self, key: Key) -> KeyHandling {
+        let state = &mut self.state;
+        if state.task_filter.is_editing {
+            return edit_filter_text(
+                key,
+                &mut state.task_filter.text,
+                &mut state.task_filter.is_editing,
+                &mut state.task_offset,
+            );
+        }
+        if state.message_filter.is_editing {
+            return edit_filter_text(
+                key,
+                &mut state.message_filter.text,
+                &mut state.message_filter.is_editing,
+                &mut state.message_offset,
+            );
+        }
+        if state.show_help {
+            state.show_help = false;
+            return KeyHandling::Handled;
+        }
+        let action = match state.key_map.action(key) {
+            Some(action) => action,
+            None => return KeyHandling::Ignored,
+        };
+        use Action::*;
+        match action {
+            Quit => return KeyHandling::Quit,
+            ToggleHelp => state.show_help = true,
+            ToggleMessages => state.hide_messages = !state.hide_messages,
+            ToggleMessagesFullscreen => state.messages_fullscreen = !state.messages_fullscreen,
+            ScrollMessagesDown => state.message_offset = state.message_offset.saturating_add(1),
+            ScrollMessagesDownPage => {
+                state.message_offset = state.message_offset.saturating_add(10)
+            }
+            ScrollTasksDown => state.task_offset = state.task_offset.saturating_add(1),
+            ScrollTasksDownPage => state.task_offset = state.task_offset.saturating_add(10),
+            ScrollMessagesUp => state.message_offset = state.message_offset.saturating_sub(1),
+            ScrollMessagesUpPage => state.message_offset = state.message_offset.saturating_sub(10),
+            ScrollTasksUp => state.task_offset = state.task_offset.saturating_sub(1),
+            ScrollTasksUpPage => state.task_offset = state.task_offset.saturating_sub(10),
+            ToggleInformation => state.hide_info = !state.hide_info,
+            ToggleMaximizeInformation => state.maximize_info = !state.maximize_info,
+            ToggleSummary => state.show_summary = !state.show_summary,
+            EditTaskFilter => state.task_filter.is_editing = true,
+            ToggleHideFinished => {
+                state.task_filter.hide_finished = !state.task_filter.hide_finished
+            }
+            ToggleHideIdle => state.task_filter.hide_idle = !state.task_filter.hide_idle,
+            ToggleHideBlocked => state.task_filter.hide_blocked = !state.task_filter.hide_blocked,
+            SelectNextTask => select_adjacent_task(state, &self.entries, Direction::Down),
+            SelectPreviousTask => select_adjacent_task(state, &self.entries, Direction::Up),
+            ToggleCollapsed => {
+                if let Some(selected) = state.selected.clone() {
+                    if !state.collapsed.remove(&selected) {
+                        state.collapsed.insert(selected);
+                    }
+                }
+            }
+            ToggleFocus => {
+                state.focused = match state.focused {
+                    Some(_) => None,
+                    None => state.selected.clone(),
+                };
+                state.follow_selection = true;
+            }
+            SearchMessages => state.message_filter.is_editing = true,
+            CycleMessageLevel => {
+                state.message_filter.cycle_level();
+                state.message_offset = 0;
+            }
+            CycleMessageOrigin => {
+                state.message_filter.cycle_origin(&self.messages);
+                state.message_offset = 0;
+            }
+            ExportMessages => {
+                self.messages_exported = export_messages(
+                    &self.progress,
+                    &self.messages_export_path,
+                    self.messages_exported,
+                )
+            }
+        }
+        KeyHandling::Handled
+    }
+}
+
+/// Edit the `text` of a filter while `is_editing`, resetting the `offset` of the filtered pane on change.
+fn edit_filter_text(
+    key: Key,
+    text: &mut String,
+    is_editing: &mut bool,
+    offset: &mut u16,
+) -> KeyHandling {
+    match key {
+        Key::Ctrl('c') => return KeyHandling::Quit,
+        Key::Esc => {
+            text.clear();
+            *is_editing = false;
+        }
+        Key::Char('\n') => *is_editing = false,
+        Key::Backspace => {
+            text.pop();
+            *offset = 0;
+        }
+        Key::Char(c) => {
+            text.push(c);
+            *offset = 0;
+        }
+        _ => return KeyHandling::Ignored,
+    }
+    KeyHandling::Handled
+}
+
+/// Append the buffered messages of `progress` sent since `exported` messages were exported to the file at `path`,
+/// one per line, and report the outcome as message.
+///
+/// Returns the amount of messages exported so far, which stays the same if writing failed.
+fn export_messages(progress: &Root, path: &Path, exported: usize) -> usize {
+    let mut messages = Vec::new();
+    let sent = progress.copy_new_messages(&mut messages, exported);
+    let write_messages = || -> io::Result<()> {
+        let file = fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)?;
+        let mut out = io::BufWriter::new(file);
+        for message in &messages {
+            writeln!(out, "{}", message)?;
+        }
+        out.flush()
+    };
+    let (level, text, exported) = match write_messages() {
+        Ok(()) => (
+            tree::MessageLevel::Info,
+            format!(
+                "appended {} messages to '{}'",
+                messages.len(),
+                path.display()
+            ),
+            sent,
+        ),
+        Err(err) => (
+            tree::MessageLevel::Failure,
+            format!("could not write messages to '{}': {}", path.display(), err),
+            exported,
+        ),
+    };
+    progress
+        .inner
+        .lock()
+        .messages
+        .lock()
+        .push_overwrite(level, "prodash".into(), &text);
+    exported
+}
+
+enum Direction {
+    Up,
+    Down,
+}
+
+/// Select the task above or below the selected one among those currently shown, skipping summary lines.
+fn select_adjacent_task(
+    state: &mut draw::State,
+    entries: &[(tree::Key, tree::Value)],
+    direction: Direction,
+) {
+    let visible_entries = draw::filter::visible_entries(state, entries);
+    let selectable: Vec<_> = visible_entries
+        .iter()
+        .map(|(key, _)| key)
+        .filter(|key| !draw::filter::is_summary(key))
+        .collect();
+    if selectable.is_empty() {
+        return;
+    }
+    let index = match (
+        state
+            .selected
+            .as_ref()
+            .map(|selected| selectable.binary_search(&selected)),
+        direction,
+    ) {
+        (None, Direction::Down) => 0,
+        (None, Direction::Up) => selectable.len() - 1,
+        (Some(Ok(index)), Direction::Down) => (index + 1).min(selectable.len() - 1),
+        (Some(Ok(index)), Direction::Up) => index.saturating_sub(1),
+        (Some(Err(index)), Direction::Down) => index.min(selectable.len() - 1),
+        (Some(Err(index)), Direction::Up) => index.saturating_sub(1),
+    };
+    state.selected = Some(selectable[index].clone());
+    state.follow_selection = true;
+}
@@ -0,0 +1,250 @@
+//! Units to display alongside the values of a [`Progress`](../tree/struct.Progress.html).
+//!
+//! ```rust
+//! use prodash::unit::{self, Scale, Unit};
+//! let tree = prodash::Tree::new();
+//! let mut download = tree.add_child("download");
+//! download.init(Some(47_185_920), Some(Unit::Bytes(Scale::Binary)));
+//! download.set(12_897_485);
+//! assert_eq!(
+//!     Unit::Bytes(Scale::Binary).display(12_897_485, Some(47_185_920)).to_string(),
+//!     "12.3 MiB / 45.0 MiB"
+//! );
+//! assert_eq!(unit::label("files").display(3, Some(10)).to_string(), "3 / 10 files");
+//! ```
+use crate::tree::ProgressStep;
+use std::{
+    borrow::Cow,
+    fmt,
+    hash::{Hash, Hasher},
+    mem,
+    sync::Arc,
+    time::Duration,
+};
+
+/// A way to display progress values with a custom unit, for use with [`Unit::Custom`](./enum.Unit.html#variant.Custom).
+///
+/// Values and their upper bound are displayed one after another, followed by the unit, as in `<value> / <upper bound><unit>`.
+pub trait DisplayValue: Send + Sync {
+    /// Write the current `value`, which may be displayed along with the `upper_bound`.
+    fn display_current_value(
+        &self,
+        w: &mut dyn fmt::Write,
+        value: ProgressStep,
+        _upper_bound: Option<ProgressStep>,
+    ) -> fmt::Result {
+        write!(w, "{}", value)
+    }
+    /// Write the `upper_bound` that is displayed after the current `value`.
+    fn display_upper_bound(
+        &self,
+        w: &mut dyn fmt::Write,
+        upper_bound: ProgressStep,
+        _value: ProgressStep,
+    ) -> fmt::Result {
+        write!(w, "{}", upper_bound)
+    }
+    /// Write the unit displayed after all values, including the separating space if there should be one.
+    fn display_unit(&self, w: &mut dyn fmt::Write, value: ProgressStep) -> fmt::Result;
+}
+
+/// The scale to use when displaying bytes
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum Scale {
+    /// Powers of 1024, like KiB and MiB
+    Binary,
+    /// Powers of 1000, like kB and MB
+    Decimal,
+}
+
+/// The unit of a progress, determining how its values are displayed.
+#[derive(Clone)]
+pub enum Unit {
+    /// A label displayed after the values, like "items" in "3 / 10 items". It should be using the plural.
+    Label(Cow<'static, str>),
+    /// Values are amounts of bytes scaled to be human-readable, as in "12.3 MiB / 45.0 MiB".
+    Bytes(Scale),
+    /// Values are seconds, displayed as in "1m 30s / 5m".
+    Duration,
+    /// Values are displayed by the given implementation.
+    Custom(Arc<dyn DisplayValue>),
+}
+
+/// Create a unit with the given `label`, like "items".
+pub fn label(label: impl Into<Cow<'static, str>>) -> Unit {
+    Unit::Label(label.into())
+}
+
+/// Create a unit displayed by the given `display` implementation.
+pub fn custom(display: impl DisplayValue + 'static) -> Unit {
+    Unit::Custom(Arc::new(display))
+}
+
+impl From<&'static str> for Unit {
+    fn from(v: &'static str) -> Self {
+        label(v)
+    }
+}
+
+impl From<String> for Unit {
+    fn from(v: String) -> Self {
+        label(v)
+    }
+}
+
+impl fmt::Debug for Unit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Unit::Label(label) => f.debug_tuple("Label").field(label).finish(),
+            Unit::Bytes(scale) => f.debug_tuple("Bytes").field(scale).finish(),
+            Unit::Duration => f.write_str("Duration"),
+            Unit::Custom(_) => f.write_str("Custom(…)"),
+        }
+    }
+}
+
+impl PartialEq for Unit {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Unit::Label(lhs), Unit::Label(rhs)) => lhs == rhs,
+            (Unit::Bytes(lhs), Unit::Bytes(rhs)) => lhs == rhs,
+            (Unit::Duration, Unit::Duration) => true,
+            (Unit::Custom(lhs), Unit::Custom(rhs)) => std::ptr::eq(
+                &**lhs as *const dyn DisplayValue as *const u8,
+                &**rhs as *const dyn DisplayValue as *const u8,
+            ),
+            _ => false,
+        }
+    }
+}
+
+impl Eq for Unit {}
+
+impl Hash for Unit {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        mem::discriminant(self).hash(state);
+        match self {
+            Unit::Label(label) => label.hash(state),
+            Unit::Bytes(scale) => scale.hash(state),
+            Unit::Duration => {}
+            Unit::Custom(d) => (&**d as *const dyn DisplayValue as *const u8).hash(state),
+        }
+    }
+}
+
+fn display_bytes(w: &mut dyn fmt::Write, value: ProgressStep, scale: Scale) -> fmt::Result {
+    let (base, units) = match scale {
+        Scale::Binary => (1024.0, ["B", "KiB", "MiB", "GiB", "TiB", "PiB", "EiB"]),
+        Scale::Decimal => (1000.0, ["B", "kB", "MB", "GB", "TB", "PB", "EB"]),
+    };
+    if (value as f64) < base {
+        return write!(w, "{} {}", value, units[0]);
+    }
+    let mut value = value as f64;
+    let mut unit = 0;
+    while value >= base && unit + 1 < units.len() {
+        value /= base;
+        unit += 1;
+    }
+    write!(w, "{:.1} {}", value, units[unit])
+}
+
+fn display_duration(w: &mut dyn fmt::Write, seconds: ProgressStep) -> fmt::Result {
+    write!(
+        w,
+        "{}",
+        humantime::format_duration(Duration::from_secs(seconds))
+    )
+}
+
+impl Unit {
+    fn display_current_value(
+        &self,
+        w: &mut dyn fmt::Write,
+        value: ProgressStep,
+        upper_bound: Option<ProgressStep>,
+    ) -> fmt::Result {
+        match self {
+            Unit::Label(_) => write!(w, "{}", value),
+            Unit::Bytes(scale) => display_bytes(w, value, *scale),
+            Unit::Duration => display_duration(w, value),
+            Unit::Custom(d) => d.display_current_value(w, value, upper_bound),
+        }
+    }
+
+    fn display_upper_bound(
+        &self,
+        w: &mut dyn fmt::Write,
+        upper_bound: ProgressStep,
+        value: ProgressStep,
+    ) -> fmt::Result {
+        match self {
+            Unit::Label(_) => write!(w, "{}", upper_bound),
+            Unit::Bytes(scale) => display_bytes(w, upper_bound, *scale),
+            Unit::Duration => display_duration(w, upper_bound),
+            Unit::Custom(d) => d.display_upper_bound(w, upper_bound, value),
+        }
+    }
+
+    fn display_unit(&self, w: &mut dyn fmt::Write, value: ProgressStep) -> fmt::Result {
+        match self {
+            Unit::Label(label) => write!(w, " {}", label),
+            Unit::Bytes(_) | Unit::Duration => Ok(()),
+            Unit::Custom(d) => d.display_unit(w, value),
+        }
+    }
+
+    /// Returns a type displaying the `value` and its optional `upper_bound` in this unit.
+    pub fn display(
+        &self,
+        value: ProgressStep,
+        upper_bound: Option<ProgressStep>,
+    ) -> UnitDisplay<'_> {
+        UnitDisplay {
+            unit: Some(self),
+            value,
+            upper_bound,
+        }
+    }
+}
+
+/// Displays a progress value, as returned by [`Unit::display(…)`](./enum.Unit.html#method.display).
+pub struct UnitDisplay<'a> {
+    unit: Option<&'a Unit>,
+    value: ProgressStep,
+    upper_bound: Option<ProgressStep>,
+}
+
+impl<'a> UnitDisplay<'a> {
+    /// Display the `value` and its optional `upper_bound` with the given `unit`, or as plain numbers if there is none.
+    pub fn new(
+        unit: Option<&'a Unit>,
+        value: ProgressStep,
+        upper_bound: Option<ProgressStep>,
+    ) -> Self {
+        UnitDisplay {
+            unit,
+            value,
+            upper_bound,
+        }
+    }
+}
+
+impl<'a> fmt::Display for UnitDisplay<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.unit {
+            Some(unit) => {
+                unit.display_current_value(f, self.value, self.upper_bound)?;
+                if let Some(upper_bound) = self.upper_bound {
+                    f.write_str(" / ")?;
+                    unit.display_upper_bound(f, upper_bound, self.value)?;
+                }
+                unit.display_unit(f, self.value)
+            }
+            None => match self.upper_bound {
+                Some(upper_bound) => write!(f, "{} / {}", self.value, upper_bound),
+                None => write!(f, "{}", self.value),
+            },
+        }
+    }
+}
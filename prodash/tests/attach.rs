@@ -0,0 +1,76 @@
+#![cfg(unix)]
+use prodash::{
+    recording::{self, Options},
+    tree::{Key, ProgressStep, Value},
+};
+use std::{
+    thread,
+    time::{Duration, Instant},
+};
+
+fn options() -> Options {
+    Options {
+        interval: Duration::from_millis(20),
+    }
+}
+
+fn names(tree: &prodash::Tree) -> Vec<String> {
+    let mut entries: Vec<(Key, Value)> = Vec::new();
+    tree.sorted_snapshot(&mut entries);
+    entries.into_iter().map(|(_, value)| value.name).collect()
+}
+
+fn steps(tree: &prodash::Tree) -> Vec<Option<ProgressStep>> {
+    let mut entries: Vec<(Key, Value)> = Vec::new();
+    tree.sorted_snapshot(&mut entries);
+    entries
+        .into_iter()
+        .map(|(_, value)| value.progress.map(|p| p.step))
+        .collect()
+}
+
+fn wait_for_names(tree: &prodash::Tree, expected: &[&str]) {
+    let deadline = Instant::now() + Duration::from_secs(5);
+    while names(tree) != expected {
+        assert!(
+            Instant::now() < deadline,
+            "expected {:?}, got {:?}",
+            expected,
+            names(tree)
+        );
+        thread::sleep(Duration::from_millis(10));
+    }
+}
+
+#[test]
+fn attached_clients_follow_the_shared_tree_and_detach_without_affecting_it() {
+    let dir = std::env::temp_dir().join(format!("prodash-attach-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let socket = dir.join("progress.sock");
+    let root = prodash::Tree::new();
+    let handle = recording::share(root.clone(), &socket, options()).unwrap();
+
+    let mut task = root.add_child("task");
+    task.init(Some(10), None);
+    let first = recording::attach(&socket).unwrap();
+    wait_for_names(&first.root(), &["task"]);
+    drop(first);
+
+    task.set(5);
+    let _other = root.add_child("other");
+    let second = recording::attach(&socket).unwrap();
+    wait_for_names(&second.root(), &["task", "other"]);
+    assert_eq!(names(&root), ["task", "other"]);
+
+    task.set(7);
+    handle.shutdown_and_wait().unwrap();
+    assert!(!socket.exists(), "the socket is removed when sharing stops");
+    let root = second.root();
+    second.wait().unwrap();
+    assert_eq!(
+        steps(&root),
+        [Some(7), None],
+        "a final frame is sent before disconnecting"
+    );
+    std::fs::remove_dir_all(&dir).ok();
+}
@@ -0,0 +1,57 @@
+use prodash::{
+    tui::{self, Dashboard, Key, KeyHandling},
+    Tree,
+};
+use std::{fs, path::PathBuf};
+
+fn lines(path: &PathBuf) -> Vec<String> {
+    fs::read_to_string(path)
+        .unwrap()
+        .lines()
+        .map(|line| line.split_once(' ').unwrap().1.to_owned())
+        .collect()
+}
+
+#[test]
+fn exporting_messages_appends_those_sent_since_the_previous_export() {
+    let path = std::env::temp_dir().join(format!("prodash-export-{}.log", std::process::id()));
+    fs::write(&path, "2020-02-13T00:51:45Z info earlier run\n").unwrap();
+    let root = Tree::new();
+    let mut task = root.add_child("task");
+    task.info("first");
+    task.fail("second");
+    let mut dashboard = Dashboard::new(
+        root,
+        tui::TuiOptions {
+            messages_export_path: path.clone(),
+            ..tui::TuiOptions::default()
+        },
+    );
+
+    assert_eq!(dashboard.handle_key(Key::Char('w')), KeyHandling::Handled);
+    assert_eq!(
+        lines(&path),
+        [
+            "info earlier run",
+            "info task → first",
+            "fail task → second"
+        ],
+        "existing files are appended to"
+    );
+
+    task.info("third");
+    assert_eq!(dashboard.handle_key(Key::Char('w')), KeyHandling::Handled);
+    let exported = format!("info prodash → appended 2 messages to '{}'", path.display());
+    assert_eq!(
+        lines(&path),
+        [
+            "info earlier run",
+            "info task → first",
+            "fail task → second",
+            exported.as_str(),
+            "info task → third",
+        ],
+        "only new messages are appended"
+    );
+    fs::remove_file(&path).ok();
+}
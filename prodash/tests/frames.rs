@@ -0,0 +1,688 @@
+//! Golden-frame tests for the terminal user interface.
+//!
+//! Each test draws a frame without a terminal and compares it with the file of the same name in `tests/frames`.
+//! Run them with `UPDATE_GOLDEN_FRAMES=1` to write the frames after intentional changes to the drawing code.
+use prodash::{
+    tree::{Item, Key as TaskKey, Message, MessageLevel, Value},
+    tui::{
+        self,
+        tui_export::{buffer::Buffer, layout::Rect, style::Color},
+        Action, Key, KeyHandling, KeyMap, Line, Theme,
+    },
+    unit::{Scale, Unit},
+    Tree, TreeOptions,
+};
+use std::{
+    fs,
+    path::Path,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+fn assert_frame(name: &str, frame: String) {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/frames")
+        .join(format!("{}.txt", name));
+    if std::env::var_os("UPDATE_GOLDEN_FRAMES").is_some() {
+        fs::write(&path, &frame).unwrap();
+        return;
+    }
+    let expected = fs::read_to_string(&path)
+        .unwrap_or_else(|err| panic!("could not read {}: {}", path.display(), err));
+    assert!(
+        frame == expected,
+        "frame '{}' differs from the golden one, run with UPDATE_GOLDEN_FRAMES=1 if this is intended\n\
+         expected:\n{}\nactual:\n{}",
+        name,
+        expected,
+        frame
+    );
+}
+
+fn message(seconds: u64, level: MessageLevel, origin: &str, message: &str) -> Message {
+    let mut message = Message::new(level, origin, message);
+    message.time = UNIX_EPOCH + Duration::from_secs(1_581_555_105 + seconds);
+    message
+}
+
+fn snapshot(tree: &Tree) -> Vec<(TaskKey, Value)> {
+    let mut entries = Vec::new();
+    tree.sorted_snapshot(&mut entries);
+    entries
+}
+
+fn render(
+    state: &mut tui::State,
+    tree: &Tree,
+    messages: &[Message],
+    width: u16,
+    height: u16,
+) -> String {
+    tui::render_to_string(
+        state,
+        &snapshot(tree),
+        messages,
+        Rect::new(0, 0, width, height),
+    )
+}
+
+fn state() -> tui::State {
+    tui::State {
+        title: "golden".into(),
+        ..tui::State::default()
+    }
+}
+
+#[test]
+fn progress_pane() {
+    let tree = Tree::new();
+    let mut group = tree.add_child("group");
+    let mut download = group.add_child("download");
+    download.init(Some(4 * 1024 * 1024), Some(Unit::Bytes(Scale::Binary)));
+    download.set(1024 * 1024);
+    let mut unpack = group.add_child("unpack");
+    unpack.init(None, Some("files".into()));
+    unpack.set(42);
+    let mut nested = unpack.add_child("nested");
+    nested.init(Some(10), None);
+    nested.set(9);
+    let mut blocked = tree.add_child("blocked");
+    blocked.init(Some(3), Some("items".into()));
+    blocked.blocked(None);
+
+    assert_frame("progress_pane", render(&mut state(), &tree, &[], 80, 10));
+}
+
+#[test]
+fn messages_pane() {
+    let tree = Tree::new();
+    let mut task = tree.add_child("task");
+    task.init(Some(2), None);
+    task.set(1);
+    let messages = [
+        message(0, MessageLevel::Info, "task", "starting"),
+        message(1, MessageLevel::Success, "task", "first item done"),
+        message(2, MessageLevel::Failure, "other task", "second item failed"),
+    ];
+
+    assert_frame(
+        "messages_pane",
+        render(&mut state(), &tree, &messages, 80, 16),
+    );
+    assert_frame(
+        "messages_pane_fullscreen",
+        render(
+            &mut tui::State {
+                messages_fullscreen: true,
+                ..state()
+            },
+            &tree,
+            &messages,
+            80,
+            16,
+        ),
+    );
+    assert_frame(
+        "messages_pane_hidden",
+        render(
+            &mut tui::State {
+                hide_messages: true,
+                ..state()
+            },
+            &tree,
+            &messages,
+            80,
+            16,
+        ),
+    );
+}
+
+#[test]
+fn message_filter() {
+    let tree = Tree::new();
+    let messages = [
+        message(0, MessageLevel::Info, "task", "starting"),
+        message(1, MessageLevel::Success, "task", "first item done"),
+        message(2, MessageLevel::Failure, "other task", "second item failed"),
+        message(3, MessageLevel::Failure, "task", "third item failed"),
+    ];
+
+    let mut filter = tui::MessageFilter::default();
+    filter.cycle_origin(&messages);
+    assert_eq!(filter.origin.as_deref(), Some("other task"));
+    filter.cycle_origin(&messages);
+    assert_eq!(filter.origin.as_deref(), Some("task"));
+    filter.cycle_level();
+    assert_eq!(filter.level, Some(MessageLevel::Debug));
+    for _ in 0..5 {
+        filter.cycle_level();
+    }
+    assert_eq!(
+        filter.level, None,
+        "all levels are shown after the last one"
+    );
+
+    assert_frame(
+        "message_filter_by_level_and_origin",
+        render(
+            &mut tui::State {
+                message_filter: tui::MessageFilter {
+                    level: Some(MessageLevel::Failure),
+                    origin: Some("task".into()),
+                    ..tui::MessageFilter::default()
+                },
+                ..state()
+            },
+            &tree,
+            &messages,
+            80,
+            12,
+        ),
+    );
+    assert_frame(
+        "message_filter_search",
+        render(
+            &mut tui::State {
+                message_filter: tui::MessageFilter {
+                    text: "ITEM".into(),
+                    is_editing: true,
+                    ..tui::MessageFilter::default()
+                },
+                ..state()
+            },
+            &tree,
+            &messages,
+            80,
+            12,
+        ),
+    );
+}
+
+#[test]
+fn message_levels_and_fields() {
+    let tree = Tree::new();
+    let with_fields = |mut message: Message, fields: &[(&str, &str)]| {
+        message.fields = fields
+            .iter()
+            .map(|&(key, value)| (key.into(), value.into()))
+            .collect();
+        message
+    };
+    let messages = [
+        message(0, MessageLevel::Debug, "index", "fetched"),
+        with_fields(
+            message(1, MessageLevel::Warning, "download", "retrying"),
+            &[("crate", "serde"), ("version", "1.0.104")],
+        ),
+        with_fields(
+            message(2, MessageLevel::Failure, "download", "gave up"),
+            &[("crate", "tokio"), ("reason", "timed out")],
+        ),
+    ];
+
+    assert_frame(
+        "message_levels_and_fields",
+        render(&mut state(), &tree, &messages, 80, 20),
+    );
+}
+
+#[test]
+fn information_pane() {
+    let tree = Tree::new();
+    let mut task = tree.add_child("task");
+    task.init(Some(4), None);
+    task.set(3);
+    let information = || {
+        vec![
+            Line::Title("Statistics".into()),
+            Line::Text("crates: 42".into()),
+            Line::Text("versions: 1337".into()),
+            Line::Title("Wide".into()),
+            Line::Text("这是中文的一些文字。".into()),
+        ]
+    };
+
+    assert_frame(
+        "information_pane",
+        render(
+            &mut tui::State {
+                information: information(),
+                ..state()
+            },
+            &tree,
+            &[],
+            80,
+            12,
+        ),
+    );
+    assert_frame(
+        "information_pane_hidden",
+        render(
+            &mut tui::State {
+                information: information(),
+                hide_info: true,
+                ..state()
+            },
+            &tree,
+            &[],
+            80,
+            12,
+        ),
+    );
+}
+
+#[test]
+fn overflow() {
+    let tree = TreeOptions {
+        initial_capacity: 30,
+        message_buffer_capacity: 30,
+        ..TreeOptions::default()
+    }
+    .create();
+    let _tasks: Vec<_> = (0..30)
+        .map(|id| {
+            let mut task = tree.add_child(format!("task {}", id));
+            task.init(Some(30), None);
+            task.set(id);
+            task
+        })
+        .collect();
+    let messages: Vec<_> = (0..10)
+        .map(|id| message(id, MessageLevel::Info, "task", &format!("message {}", id)))
+        .collect();
+
+    let mut state = tui::State {
+        task_offset: 5,
+        message_offset: 2,
+        ..state()
+    };
+    assert_frame("overflow", render(&mut state, &tree, &messages, 70, 16));
+
+    let mut state = tui::State {
+        task_offset: u16::MAX,
+        ..self::state()
+    };
+    render(&mut state, &tree, &[], 70, 16);
+    assert_eq!(
+        state.task_offset, 16,
+        "offsets are limited to the amount of tasks that can be scrolled past"
+    );
+}
+
+#[test]
+fn tiny_windows() {
+    let tree = Tree::new();
+    let mut task = tree.add_child("a task with a long name");
+    task.init(Some(10), Some("items".into()));
+    task.set(5);
+    let messages = [message(0, MessageLevel::Info, "a task", "a message")];
+    let information = || vec![Line::Title("Info".into()), Line::Text("text".into())];
+
+    for width in 0..12 {
+        for height in 0..8 {
+            render(
+                &mut tui::State {
+                    information: information(),
+                    ..state()
+                },
+                &tree,
+                &messages,
+                width,
+                height,
+            );
+        }
+    }
+    assert_frame("tiny_window", render(&mut state(), &tree, &messages, 12, 5));
+    assert_frame(
+        "narrow_window",
+        render(&mut state(), &tree, &messages, 30, 8),
+    );
+}
+
+#[test]
+fn task_filter() {
+    let tree = Tree::new();
+    let mut group = tree.add_child("crates");
+    let mut download = group.add_child("download");
+    let mut tokio = download.add_child("tokio");
+    tokio.init(Some(10), None);
+    tokio.set(10);
+    let mut serde = download.add_child("serde");
+    serde.init(Some(10), None);
+    serde.set(3);
+    let mut other = tree.add_child("index");
+    other.init(None, None);
+
+    assert_frame(
+        "task_filter_by_name",
+        render(
+            &mut tui::State {
+                task_filter: tui::TaskFilter {
+                    text: "SER".into(),
+                    is_editing: true,
+                    ..tui::TaskFilter::default()
+                },
+                ..state()
+            },
+            &tree,
+            &[],
+            60,
+            8,
+        ),
+    );
+    assert_frame(
+        "task_filter_hide_finished",
+        render(
+            &mut tui::State {
+                task_filter: tui::TaskFilter {
+                    hide_finished: true,
+                    ..tui::TaskFilter::default()
+                },
+                ..state()
+            },
+            &tree,
+            &[],
+            60,
+            8,
+        ),
+    );
+}
+
+/// Returns a tree with a group of nested tasks, along with the tasks which would be removed when dropped.
+fn nested_tasks() -> (Tree, Vec<Item>) {
+    let tree = Tree::new();
+    let mut group = tree.add_child("crates");
+    let mut download = group.add_child("download");
+    let mut tokio = download.add_child("tokio");
+    tokio.init(Some(10), None);
+    tokio.set(10);
+    let mut serde = download.add_child("serde");
+    serde.init(Some(10), None);
+    serde.set(3);
+    serde.blocked(None);
+    let mut other = tree.add_child("index");
+    other.init(None, None);
+    (tree, vec![group, download, tokio, serde, other])
+}
+
+#[test]
+fn collapse_and_focus() {
+    let (tree, _tasks) = nested_tasks();
+    let entries = snapshot(&tree);
+    let key_of = |name: &str| {
+        entries
+            .iter()
+            .find(|(_, value)| value.name == name)
+            .map(|(key, _)| key.clone())
+            .unwrap()
+    };
+
+    assert_frame(
+        "collapsed_task",
+        render(
+            &mut tui::State {
+                collapsed: vec![key_of("download")].into_iter().collect(),
+                ..state()
+            },
+            &tree,
+            &[],
+            80,
+            6,
+        ),
+    );
+    assert_frame(
+        "focused_task",
+        render(
+            &mut tui::State {
+                focused: Some(key_of("download")),
+                ..state()
+            },
+            &tree,
+            &[],
+            80,
+            6,
+        ),
+    );
+
+    let mut state = tui::State {
+        selected: Some(key_of("serde")),
+        follow_selection: true,
+        ..state()
+    };
+    let buf = tui::render_to_buffer(&mut state, &entries, &[], Rect::new(0, 0, 80, 4));
+    assert_eq!(
+        state.task_offset, 3,
+        "the selected task is scrolled into view"
+    );
+    let is_highlighted = |y| {
+        buf.get(1, y)
+            .style
+            .modifier
+            .contains(tui::tui_export::style::Modifier::REVERSED)
+    };
+    assert!(is_highlighted(1), "the selected task is highlighted");
+    assert!(!is_highlighted(2), "other tasks are not highlighted");
+}
+
+#[test]
+fn dashboard_widget() {
+    let (tree, _tasks) = nested_tasks();
+    let mut dashboard = tui::Dashboard::new(
+        tree,
+        tui::TuiOptions {
+            title: "golden".into(),
+            ..tui::TuiOptions::default()
+        },
+    );
+    let bound = Rect::new(0, 0, 80, 6);
+    let mut buf = Buffer::empty(bound);
+    dashboard.draw(bound, &mut buf);
+
+    for key in &[Key::Down, Key::Down, Key::Char('\n')] {
+        assert_eq!(dashboard.handle_key(*key), KeyHandling::Handled);
+    }
+    assert_eq!(dashboard.handle_key(Key::F(1)), KeyHandling::Ignored);
+    dashboard.draw(bound, &mut buf);
+    assert_frame("collapsed_task", tui::buffer_to_string(&buf));
+
+    for key in &[Key::Char('\n'), Key::Char('z')] {
+        assert_eq!(dashboard.handle_key(*key), KeyHandling::Handled);
+    }
+    dashboard.draw(bound, &mut buf);
+    assert_frame("focused_task", tui::buffer_to_string(&buf));
+
+    assert_eq!(dashboard.handle_key(Key::Char('/')), KeyHandling::Handled);
+    assert_eq!(
+        dashboard.handle_key(Key::Char('q')),
+        KeyHandling::Handled,
+        "keys edit the filter text"
+    );
+    assert_eq!(dashboard.handle_key(Key::Esc), KeyHandling::Handled);
+    assert_eq!(dashboard.handle_key(Key::Char('q')), KeyHandling::Quit);
+}
+
+#[test]
+fn key_map_and_help_overlay() {
+    let (tree, _tasks) = nested_tasks();
+    let mut key_map = KeyMap::default();
+    key_map
+        .bind(Key::Char('x'), Action::Quit)
+        .bind(Key::Char('j'), Action::SelectNextTask)
+        .unbind(Key::Char('q'))
+        .unbind(Key::Char('d'));
+    let mut dashboard = tui::Dashboard::new(
+        tree,
+        tui::TuiOptions {
+            title: "golden".into(),
+            key_map,
+            ..tui::TuiOptions::default()
+        },
+    );
+    let bound = Rect::new(0, 0, 80, 34);
+    let mut buf = Buffer::empty(bound);
+    dashboard.draw(bound, &mut buf);
+
+    assert_eq!(dashboard.handle_key(Key::Char('q')), KeyHandling::Ignored);
+    assert_eq!(dashboard.handle_key(Key::Char('j')), KeyHandling::Handled);
+    assert!(dashboard.state().selected.is_some(), "'j' selects now");
+    assert_eq!(dashboard.state().task_offset, 0, "and doesn't scroll");
+
+    assert_eq!(dashboard.handle_key(Key::Char('?')), KeyHandling::Handled);
+    dashboard.draw(bound, &mut buf);
+    assert_frame("help_overlay", tui::buffer_to_string(&buf));
+
+    assert_eq!(
+        dashboard.handle_key(Key::Char('x')),
+        KeyHandling::Handled,
+        "any key hides the help"
+    );
+    assert!(!dashboard.state().show_help);
+    assert_eq!(dashboard.handle_key(Key::Char('x')), KeyHandling::Quit);
+}
+
+#[test]
+fn themes() {
+    let tree = Tree::new();
+    let mut running = tree.add_child("running");
+    running.init(Some(10), None);
+    running.set(5);
+    let mut blocked = tree.add_child("blocked");
+    blocked.init(Some(10), None);
+    blocked.set(3);
+    blocked.blocked(None);
+    let messages = [message(0, MessageLevel::Failure, "running", "failed")];
+    let entries = snapshot(&tree);
+
+    for theme in &[Theme::dark(), Theme::light(), Theme::monochrome()] {
+        let buf = tui::render_to_buffer(
+            &mut tui::State {
+                theme: *theme,
+                ..state()
+            },
+            &entries,
+            &messages,
+            Rect::new(0, 0, 80, 12),
+        );
+        assert_frame("themes", tui::buffer_to_string(&buf));
+        let has_style = |style| buf.content.iter().any(|cell| cell.style == style);
+        assert!(has_style(theme.progress_bar), "{:?}", theme);
+        assert!(has_style(theme.progress_bar_blocked), "{:?}", theme);
+        assert!(has_style(theme.message_failure), "{:?}", theme);
+    }
+
+    let buf = tui::render_to_buffer(
+        &mut tui::State {
+            theme: Theme::monochrome(),
+            ..state()
+        },
+        &entries,
+        &messages,
+        Rect::new(0, 0, 80, 12),
+    );
+    assert!(
+        buf.content
+            .iter()
+            .all(|cell| cell.style.fg == Color::Reset && cell.style.bg == Color::Reset),
+        "monochrome uses no colors"
+    );
+}
+
+#[test]
+fn summary() {
+    let tree = TreeOptions {
+        retention: Some(Duration::from_secs(3600)),
+        ..TreeOptions::default()
+    }
+    .create();
+    let mut download = tree.add_child("download");
+    download.init(Some(10), None);
+    download.set(5);
+    let mut unpack = download.add_child("unpack");
+    unpack.init(Some(4), None);
+    unpack.set(4);
+    let mut index = tree.add_child("index");
+    index.init(None, None);
+    tree.add_child("checksum").done("matches");
+    tree.add_child("upload").fail("connection refused");
+    drop(tree.add_child("cancelled"));
+
+    assert_frame(
+        "summary",
+        render(
+            &mut tui::State {
+                show_summary: true,
+                started_at: Some(SystemTime::now() - Duration::from_secs(3725)),
+                ..state()
+            },
+            &tree,
+            &[],
+            80,
+            10,
+        ),
+    );
+
+    download.done("downloaded");
+    unpack.fail("could not unpack");
+    drop(unpack);
+    drop(download);
+    let mut dashboard = tui::Dashboard::new(
+        tree,
+        tui::TuiOptions {
+            show_summary: true,
+            ..tui::TuiOptions::default()
+        },
+    );
+    let bound = Rect::new(0, 0, 80, 10);
+    let mut buf = Buffer::empty(bound);
+    dashboard.draw(bound, &mut buf);
+    assert!(
+        tui::buffer_to_string(&buf).contains(" 75% of 2 tasks │ 0s elapsed │ 2 done │ 2 failed "),
+        "tasks are counted by their outcome, not by their messages"
+    );
+
+    assert_eq!(dashboard.handle_key(Key::Char('S')), KeyHandling::Handled);
+    dashboard.draw(bound, &mut buf);
+    assert!(!tui::buffer_to_string(&buf).contains("elapsed"));
+}
+
+#[test]
+fn retained_tasks() {
+    let tree = TreeOptions {
+        retention: Some(Duration::from_secs(60)),
+        ..TreeOptions::default()
+    }
+    .create();
+    let mut group = tree.add_child("crates");
+    let mut succeeded = group.add_child("serde");
+    succeeded.init(Some(10), None);
+    succeeded.set(10);
+    succeeded.done("downloaded");
+    let mut failed = group.add_child("tokio");
+    failed.init(Some(10), None);
+    failed.set(4);
+    failed.fail("connection reset");
+    let mut cancelled = group.add_child("rand");
+    cancelled.init(None, Some("files".into()));
+    cancelled.set(3);
+    let mut running = group.add_child("syn");
+    running.init(Some(10), None);
+    running.set(2);
+    drop((succeeded, failed, cancelled));
+
+    assert_frame("retained_tasks", render(&mut state(), &tree, &[], 80, 7));
+    assert_frame(
+        "retained_tasks_hidden",
+        render(
+            &mut tui::State {
+                task_filter: tui::TaskFilter {
+                    hide_finished: true,
+                    ..tui::TaskFilter::default()
+                },
+                ..state()
+            },
+            &tree,
+            &[],
+            80,
+            7,
+        ),
+    );
+}
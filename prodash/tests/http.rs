@@ -0,0 +1,149 @@
+use prodash::http;
+use std::{
+    io::{BufRead, BufReader, Read, Write},
+    net::{SocketAddr, TcpStream},
+    thread,
+    time::{Duration, Instant},
+};
+
+fn send(addr: SocketAddr, method: &str, path: &str) -> TcpStream {
+    let mut stream = TcpStream::connect(addr).unwrap();
+    stream
+        .set_read_timeout(Some(Duration::from_secs(5)))
+        .unwrap();
+    write!(
+        stream,
+        "{} {} HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n",
+        method, path
+    )
+    .unwrap();
+    stream
+}
+
+fn request(addr: SocketAddr, method: &str, path: &str) -> (u16, serde_json::Value) {
+    let mut response = String::new();
+    send(addr, method, path)
+        .read_to_string(&mut response)
+        .unwrap();
+    let status = response[9..12].parse().unwrap();
+    let body = &response[response.find("\r\n\r\n").unwrap() + 4..];
+    (status, serde_json::from_str(body).unwrap())
+}
+
+/// Returns the data of the next `snapshot` event, skipping headers and comments.
+fn next_event(events: &mut impl BufRead) -> serde_json::Value {
+    let mut line = String::new();
+    loop {
+        line.clear();
+        assert_ne!(events.read_line(&mut line).unwrap(), 0, "stream ended");
+        if let Some(data) = line.strip_prefix("data: ") {
+            return serde_json::from_str(data).unwrap();
+        }
+    }
+}
+
+fn options() -> http::Options {
+    http::Options {
+        interval: Duration::from_millis(20),
+    }
+}
+
+#[test]
+fn endpoints_answer_with_json() {
+    let root = prodash::Tree::new();
+    let handle = http::serve(root.clone(), "127.0.0.1:0", options()).unwrap();
+    let mut parent = root.add_child("parent");
+    let mut child = parent.add_child("child");
+    child.init(Some(4), Some("files".into()));
+    child.set(1);
+    parent.message_with_fields(
+        prodash::tree::MessageLevel::Warning,
+        "slow",
+        vec![("crate".to_owned(), "a".to_owned())],
+    );
+
+    let (status, tasks) = request(handle.addr(), "GET", "/tasks");
+    assert_eq!(status, 200);
+    assert_eq!(tasks[0]["name"], "parent");
+    assert!(tasks[0]["progress"].is_null());
+    assert_eq!(tasks[1]["level"], 2);
+    assert_eq!(tasks[1]["progress"]["step"], 1);
+    assert_eq!(tasks[1]["progress"]["done_at"], 4);
+    assert_eq!(tasks[1]["progress"]["fraction"], 0.25);
+    assert_eq!(tasks[1]["progress"]["display"], "1 / 4 files");
+
+    let (status, messages) = request(handle.addr(), "GET", "/messages");
+    assert_eq!(status, 200);
+    assert_eq!(messages[0]["level"], "warning");
+    assert_eq!(messages[0]["origin"], "parent");
+    assert_eq!(messages[0]["message"], "slow");
+    assert_eq!(messages[0]["fields"]["crate"], "a");
+
+    let (status, snapshot) = request(handle.addr(), "GET", "/");
+    assert_eq!(status, 200);
+    assert_eq!(snapshot["tasks"], tasks);
+    assert_eq!(snapshot["messages"], messages);
+
+    let (status, body) = request(handle.addr(), "GET", "/nothing");
+    assert_eq!(status, 404);
+    assert!(body["error"].is_string());
+    let (status, _) = request(handle.addr(), "POST", "/tasks");
+    assert_eq!(status, 405);
+    handle.shutdown_and_wait();
+}
+
+#[test]
+fn event_stream_sends_snapshots_as_the_tree_changes() {
+    let root = prodash::Tree::new();
+    let handle = http::serve(root.clone(), "127.0.0.1:0", options()).unwrap();
+    let mut events = BufReader::new(send(handle.addr(), "GET", "/events"));
+
+    let mut status = String::new();
+    events.read_line(&mut status).unwrap();
+    assert_eq!(status.trim_end(), "HTTP/1.1 200 OK");
+    let snapshot = next_event(&mut events);
+    assert_eq!(snapshot["tasks"], serde_json::json!([]));
+
+    let _task = root.add_child("task");
+    let snapshot = next_event(&mut events);
+    assert_eq!(snapshot["tasks"][0]["name"], "task");
+
+    handle.shutdown_and_wait();
+    let mut rest = String::new();
+    events.read_to_string(&mut rest).unwrap();
+}
+
+#[test]
+fn shutdown_does_not_wait_for_clients_which_stopped_reading() {
+    let root = prodash::Tree::new();
+    let mut tasks: Vec<_> = (0..1000)
+        .map(|id| root.add_child(format!("{:-<100}", id)))
+        .collect();
+    for task in &mut tasks {
+        task.init(None, None);
+    }
+    let handle = http::serve(
+        root.clone(),
+        "127.0.0.1:0",
+        http::Options {
+            interval: Duration::from_millis(1),
+        },
+    )
+    .unwrap();
+    let _stalled = send(handle.addr(), "GET", "/events");
+
+    // change the tree until the stream had plenty of time to fill all buffers
+    let start = Instant::now();
+    let mut step = 0;
+    while start.elapsed() < Duration::from_millis(500) {
+        step += 1;
+        for task in &mut tasks {
+            task.set(step);
+        }
+        thread::sleep(Duration::from_millis(1));
+    }
+
+    let start = Instant::now();
+    handle.shutdown_and_wait();
+    assert!(start.elapsed() < Duration::from_secs(1));
+}
@@ -0,0 +1,136 @@
+use parking_lot::Mutex;
+use prodash::{line, tree::Message, Tree, TreeOptions};
+use std::{
+    io,
+    sync::Arc,
+    thread,
+    time::{Duration, Instant},
+};
+
+/// A writer whose output can be read while the renderer owns it.
+#[derive(Clone, Default)]
+struct Output(Arc<Mutex<Vec<u8>>>);
+
+impl Output {
+    fn lines(&self) -> Vec<String> {
+        String::from_utf8_lossy(&self.0.lock())
+            .lines()
+            .map(ToOwned::to_owned)
+            .collect()
+    }
+
+    fn wait_for_line(&self, line: &str) {
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while !self.lines().iter().any(|l| l == line) {
+            assert!(Instant::now() < deadline, "'{}' was never printed", line);
+            thread::sleep(Duration::from_millis(5));
+        }
+    }
+}
+
+impl io::Write for Output {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.lock().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+fn options(throttle: Duration) -> line::Options {
+    line::Options {
+        throttle,
+        timestamp: false,
+        ..line::Options::default()
+    }
+}
+
+#[test]
+fn changes_within_the_throttle_are_reported_once() {
+    let (root, out) = (Tree::new(), Output::default());
+    let handle = line::render(
+        out.clone(),
+        root.clone(),
+        options(Duration::from_secs(3600)),
+    );
+    let mut task = root.add_child("task");
+    task.init(Some(10), Some("items".into()));
+    for step in 1..=5 {
+        task.set(step);
+    }
+    task.info("halfway");
+
+    handle.shutdown_and_wait().unwrap();
+    assert_eq!(
+        out.lines(),
+        ["info task: halfway", "task 5 / 10 items (50%)"]
+    );
+}
+
+#[test]
+fn each_message_and_change_is_printed_once_across_reports() {
+    let (root, out) = (Tree::new(), Output::default());
+    let mut task = root.add_child("task");
+    task.init(None, None);
+    task.info("first");
+    task.info("first");
+    let handle = line::render(
+        out.clone(),
+        root.clone(),
+        options(Duration::from_millis(10)),
+    );
+    out.wait_for_line("info task: first");
+    // give the renderer the chance to print unchanged state again
+    thread::sleep(Duration::from_millis(50));
+
+    task.info("second");
+    handle.shutdown_and_wait().unwrap();
+    assert_eq!(
+        out.lines(),
+        [
+            "info task: first",
+            "info task: first",
+            "task 0",
+            "info task: second"
+        ]
+    );
+}
+
+#[test]
+fn only_messages_sent_since_the_previous_copy_are_copied() {
+    let root = TreeOptions {
+        message_buffer_capacity: 5,
+        ..TreeOptions::default()
+    }
+    .create();
+    let mut task = root.add_child("task");
+    let mut messages: Vec<Message> = Vec::new();
+    let texts = |messages: &[Message]| {
+        messages
+            .iter()
+            .map(|m| m.message.clone())
+            .collect::<Vec<_>>()
+    };
+
+    let seen = root.copy_new_messages(&mut messages, 0);
+    assert_eq!((seen, messages.len()), (0, 0));
+
+    task.info("a");
+    task.info("a");
+    let seen = root.copy_new_messages(&mut messages, seen);
+    assert_eq!((seen, texts(&messages)), (2, vec!["a".into(), "a".into()]));
+
+    task.info("b");
+    let seen = root.copy_new_messages(&mut messages, seen);
+    assert_eq!((seen, texts(&messages)), (3, vec!["b".into()]));
+
+    let seen = root.copy_new_messages(&mut messages, seen);
+    assert_eq!((seen, messages.len()), (3, 0), "nothing new was sent");
+
+    task.info("c");
+    task.info("d");
+    let seen = root.copy_new_messages(&mut messages, seen);
+    assert_eq!((seen, texts(&messages)), (5, vec!["c".into(), "d".into()]));
+}
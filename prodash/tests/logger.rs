@@ -0,0 +1,61 @@
+use log::{Level, LevelFilter, Log, Record};
+use prodash::{
+    logger::{self, Logger},
+    tree::MessageLevel,
+    Tree,
+};
+use std::fs;
+
+fn log(logger: &Logger, level: Level, target: &str, message: &str) {
+    logger.log(
+        &Record::builder()
+            .level(level)
+            .target(target)
+            .args(format_args!("{}", message))
+            .build(),
+    );
+}
+
+#[test]
+fn records_become_messages_and_are_passed_through() {
+    let path = std::env::temp_dir().join(format!("prodash-logger-{}.log", std::process::id()));
+    fs::remove_file(&path).ok();
+    let root = Tree::new();
+    let logger = Logger::new(
+        &root,
+        logger::Options {
+            level: LevelFilter::Warn,
+            pass_through: Some(path.clone()),
+        },
+    )
+    .unwrap();
+
+    log(&logger, Level::Error, "criner::engine", "it failed");
+    log(&logger, Level::Warn, "criner::export", "it is slow");
+    log(&logger, Level::Info, "criner::engine", "ignored");
+
+    let mut messages = Vec::new();
+    root.copy_messages(&mut messages);
+    let messages: Vec<_> = messages
+        .iter()
+        .map(|m| (m.level, m.origin.as_str(), m.message.as_str()))
+        .collect();
+    assert_eq!(
+        messages,
+        vec![
+            (MessageLevel::Failure, "criner::engine", "it failed"),
+            (MessageLevel::Warning, "criner::export", "it is slow"),
+        ]
+    );
+
+    let lines = fs::read_to_string(&path).unwrap();
+    fs::remove_file(&path).ok();
+    let lines: Vec<_> = lines.lines().collect();
+    assert_eq!(
+        lines.len(),
+        2,
+        "only records passing the filter are written"
+    );
+    assert!(lines[0].ends_with(" fail criner::engine → it failed"));
+    assert!(lines[1].ends_with(" warn criner::export → it is slow"));
+}
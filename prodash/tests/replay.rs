@@ -0,0 +1,184 @@
+use prodash::{
+    recording::{self, Player, Recording},
+    tree::{Key, ProgressStep, Value},
+    unit::{self, DisplayValue},
+    Tree,
+};
+use std::{fmt, time::Duration};
+
+fn task(id: u64, name: &str, step: ProgressStep) -> String {
+    format!(
+        r#"{{"key":[{}],"name":"{}","progress":{{"step":{},"done_at":null,"unit":null,"blocked":null,"throughput":null}}}}"#,
+        id, name, step
+    )
+}
+
+fn frame(time_ms: u64, tasks: &[String], removed: &[u64]) -> String {
+    format!(
+        r#"{{"time_ms":{},"tasks":[{}],"removed":[{}],"messages":[]}}"#,
+        time_ms,
+        tasks.join(","),
+        removed
+            .iter()
+            .map(|id| format!("[{}]", id))
+            .collect::<Vec<_>>()
+            .join(",")
+    )
+}
+
+fn recording(frames: &[String]) -> Recording {
+    let mut lines = vec![r#"{"version":1,"messages_capacity":10}"#.to_string()];
+    lines.extend(frames.iter().cloned());
+    Recording::read_from(lines.join("\n").as_bytes()).unwrap()
+}
+
+/// A task 'a' making progress over 3 seconds, and a task 'b' which is removed after 2 seconds.
+fn player() -> Player {
+    Player::new(recording(&[
+        frame(1000, &[task(1, "a", 1), task(2, "b", 0)], &[]),
+        frame(2000, &[task(1, "a", 2)], &[]),
+        frame(3000, &[], &[2]),
+        frame(4000, &[task(1, "a", 4)], &[]),
+    ]))
+}
+
+fn steps(player: &Player) -> Vec<(String, Option<ProgressStep>)> {
+    let mut entries: Vec<(Key, Value)> = Vec::new();
+    player.root().sorted_snapshot(&mut entries);
+    entries
+        .into_iter()
+        .map(|(_, value)| (value.name, value.progress.map(|p| p.step)))
+        .collect()
+}
+
+fn secs(secs: f32) -> Duration {
+    Duration::from_secs_f32(secs)
+}
+
+#[test]
+fn seeking_forward_applies_all_frames_up_to_the_position() {
+    let mut player = player();
+    assert_eq!(player.duration(), secs(3.0));
+    assert_eq!(
+        steps(&player),
+        vec![("a".into(), Some(1)), ("b".into(), Some(0))],
+        "the first frame is shown at the beginning"
+    );
+
+    player.seek(secs(1.5));
+    assert_eq!(
+        steps(&player),
+        vec![("a".into(), Some(2)), ("b".into(), Some(0))]
+    );
+
+    player.seek(secs(2.5));
+    assert_eq!(steps(&player), vec![("a".into(), Some(2))]);
+
+    player.seek(secs(10.0));
+    assert_eq!(player.position(), secs(3.0), "seeking is clamped");
+    assert_eq!(steps(&player), vec![("a".into(), Some(4))]);
+}
+
+#[test]
+fn seeking_backward_restores_removed_tasks_and_earlier_progress() {
+    let mut player = player();
+    player.seek(player.duration());
+    assert_eq!(steps(&player), vec![("a".into(), Some(4))]);
+
+    player.seek(secs(1.0));
+    assert_eq!(
+        steps(&player),
+        vec![("a".into(), Some(2)), ("b".into(), Some(0))]
+    );
+
+    player.seek(Duration::default());
+    assert_eq!(
+        steps(&player),
+        vec![("a".into(), Some(1)), ("b".into(), Some(0))]
+    );
+}
+
+#[test]
+fn advancing_respects_speed_and_pause() {
+    let mut player = player();
+    player.set_speed(2.0);
+    player.advance(secs(1.0));
+    assert_eq!(player.position(), secs(2.0));
+    assert_eq!(steps(&player), vec![("a".into(), Some(2))]);
+
+    player.set_paused(true);
+    player.advance(secs(1.0));
+    assert_eq!(player.position(), secs(2.0), "paused players stay put");
+
+    player.set_paused(false);
+    player.set_speed(0.5);
+    player.advance(secs(1.0));
+    assert_eq!(player.position(), secs(2.5));
+    assert_eq!(steps(&player), vec![("a".into(), Some(2))]);
+}
+
+#[test]
+fn the_duration_ends_at_the_latest_frame_even_if_the_clock_stepped_back() {
+    let mut player = Player::new(recording(&[
+        frame(1000, &[task(1, "a", 1)], &[]),
+        frame(3000, &[task(1, "a", 3)], &[]),
+        frame(2000, &[task(1, "a", 2)], &[]),
+    ]));
+    assert_eq!(player.duration(), secs(2.0));
+
+    player.seek(player.duration());
+    assert_eq!(steps(&player), vec![("a".into(), Some(2))]);
+
+    let stepped_back_before_the_start = recording(&[
+        frame(5000, &[task(1, "a", 1)], &[]),
+        frame(4000, &[task(1, "a", 2)], &[]),
+    ]);
+    assert_eq!(
+        stepped_back_before_the_start.duration(),
+        Duration::default()
+    );
+}
+
+struct Percent;
+
+impl DisplayValue for Percent {
+    fn display_current_value(
+        &self,
+        w: &mut dyn fmt::Write,
+        value: ProgressStep,
+        upper_bound: Option<ProgressStep>,
+    ) -> fmt::Result {
+        write!(w, "{}%", value * 100 / upper_bound.unwrap_or(100))
+    }
+
+    fn display_unit(&self, w: &mut dyn fmt::Write, value: ProgressStep) -> fmt::Result {
+        w.write_str(if value == 1 { " file" } else { " files" })
+    }
+}
+
+#[test]
+fn custom_units_are_recorded_as_the_label_they_display() {
+    let tree = Tree::new();
+    let mut task = tree.add_child("copy");
+    task.init(Some(4), Some(unit::custom(Percent)));
+    task.set(2);
+
+    let out = recording::record(Vec::new(), tree, recording::Options::default())
+        .shutdown_and_wait()
+        .unwrap();
+    let player = Player::new(Recording::read_from(out.as_slice()).unwrap());
+    let mut entries: Vec<(Key, Value)> = Vec::new();
+    player.root().sorted_snapshot(&mut entries);
+    let progress = entries[0].1.progress.as_ref().unwrap();
+    assert_eq!(progress.unit, Some(unit::label("files")));
+    assert_eq!(
+        progress
+            .unit
+            .as_ref()
+            .unwrap()
+            .display(2, Some(4))
+            .to_string(),
+        "2 / 4 files",
+        "values are plain numbers"
+    );
+}
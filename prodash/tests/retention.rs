@@ -0,0 +1,80 @@
+use prodash::{
+    tree::{Key, Outcome, Value},
+    TreeOptions,
+};
+use std::{thread, time::Duration};
+
+fn outcomes(tree: &prodash::Tree) -> Vec<(String, Option<Outcome>)> {
+    let mut entries: Vec<(Key, Value)> = Vec::new();
+    tree.sorted_snapshot(&mut entries);
+    entries
+        .into_iter()
+        .map(|(_, value)| (value.name, value.outcome))
+        .collect()
+}
+
+fn tree_with_retention(retention: Duration) -> prodash::Tree {
+    TreeOptions {
+        retention: Some(retention),
+        ..TreeOptions::default()
+    }
+    .create()
+}
+
+#[test]
+fn dropped_items_are_kept_with_their_outcome_while_retained() {
+    let tree = tree_with_retention(Duration::from_secs(3600));
+    let mut succeeded = tree.add_child("succeeded");
+    succeeded.fail("first attempt failed");
+    succeeded.done("second attempt succeeded");
+    let mut failed = tree.add_child("failed");
+    failed.info("trying");
+    failed.fail("gave up");
+    let running = tree.add_child("running");
+    drop((succeeded, failed, tree.add_child("cancelled")));
+
+    assert_eq!(
+        outcomes(&tree),
+        vec![
+            ("succeeded".into(), Some(Outcome::Succeeded)),
+            ("failed".into(), Some(Outcome::Failed)),
+            ("running".into(), None),
+            ("cancelled".into(), Some(Outcome::Cancelled)),
+        ]
+    );
+    drop(running);
+}
+
+#[test]
+fn dropped_items_are_removed_once_the_retention_expired() {
+    let tree = tree_with_retention(Duration::from_millis(1));
+    let running = tree.add_child("running");
+    drop(tree.add_child("dropped"));
+    thread::sleep(Duration::from_millis(10));
+    assert_eq!(outcomes(&tree), vec![("running".into(), None)]);
+    drop(running);
+}
+
+#[test]
+fn expired_items_are_removed_when_items_are_added_or_dropped() {
+    let tree = tree_with_retention(Duration::from_millis(1));
+    drop(tree.add_child("first"));
+    thread::sleep(Duration::from_millis(10));
+    let second = tree.add_child("second");
+    assert_eq!(tree.num_tasks(), 1, "adding removes 'first'");
+
+    let third = tree.add_child("third");
+    drop(second);
+    thread::sleep(Duration::from_millis(10));
+    drop(third);
+    assert_eq!(tree.num_tasks(), 1, "dropping 'third' removes 'second'");
+}
+
+#[test]
+fn dropped_items_are_removed_right_away_without_retention() {
+    let tree = TreeOptions::default().create();
+    let mut task = tree.add_child("task");
+    task.done("finished");
+    drop(task);
+    assert!(outcomes(&tree).is_empty());
+}
@@ -0,0 +1,149 @@
+use prodash::tree::{Key, Progress, ProgressStep, Throughput, ThroughputTracker, Value};
+use std::time::{Duration, Instant, SystemTime};
+
+fn entries(step: ProgressStep, done_at: Option<ProgressStep>) -> Vec<(Key, Value)> {
+    vec![(
+        Key::default(),
+        Value {
+            name: "task".into(),
+            progress: Some(Progress {
+                step,
+                done_at,
+                ..Progress::default()
+            }),
+            outcome: None,
+        },
+    )]
+}
+
+struct Clock {
+    start: Instant,
+    wall_clock_start: SystemTime,
+}
+
+impl Clock {
+    fn new() -> Self {
+        Clock {
+            start: Instant::now(),
+            wall_clock_start: SystemTime::now(),
+        }
+    }
+
+    /// Sample a task at `step` after `elapsed_ms`, and return its throughput.
+    fn sample(
+        &self,
+        tracker: &mut ThroughputTracker,
+        elapsed_ms: u64,
+        step: ProgressStep,
+        done_at: Option<ProgressStep>,
+    ) -> Option<Throughput> {
+        let elapsed = Duration::from_millis(elapsed_ms);
+        let mut entries = entries(step, done_at);
+        tracker.update_at(
+            &mut entries,
+            self.start + elapsed,
+            self.wall_clock_start + elapsed,
+        );
+        entries[0].1.progress.as_ref().unwrap().throughput
+    }
+
+    fn at(&self, elapsed_ms: u64) -> SystemTime {
+        self.wall_clock_start + Duration::from_millis(elapsed_ms)
+    }
+}
+
+fn throughput(
+    steps: ProgressStep,
+    timespan_ms: u64,
+    eta: Option<SystemTime>,
+) -> Option<Throughput> {
+    Some(Throughput {
+        steps,
+        timespan: Duration::from_millis(timespan_ms),
+        eta,
+    })
+}
+
+#[test]
+fn throughput_and_eta_are_computed_from_the_first_and_last_sample() {
+    let (clock, mut tracker) = (Clock::new(), ThroughputTracker::default());
+    assert_eq!(
+        clock.sample(&mut tracker, 0, 0, Some(45)),
+        None,
+        "a single sample isn't enough"
+    );
+    assert_eq!(
+        clock.sample(&mut tracker, 1000, 10, Some(45)),
+        throughput(10, 1000, Some(clock.at(4500)))
+    );
+    assert_eq!(
+        clock.sample(&mut tracker, 2000, 30, Some(45)),
+        throughput(30, 2000, Some(clock.at(3000))),
+        "the ETA uses the average rate"
+    );
+    assert_eq!(
+        clock.sample(&mut tracker, 3000, 30, None),
+        throughput(30, 3000, None),
+        "unbounded progress has no ETA"
+    );
+}
+
+#[test]
+fn frequent_snapshots_do_not_add_samples() {
+    let (clock, mut tracker) = (Clock::new(), ThroughputTracker::default());
+    clock.sample(&mut tracker, 0, 0, None);
+    clock.sample(&mut tracker, 1000, 10, None);
+    for ms in (1010..1100).step_by(10) {
+        assert_eq!(
+            clock.sample(&mut tracker, ms, 1000, None),
+            throughput(10, 1000, None),
+            "samples are taken at most once per 100ms"
+        );
+    }
+    assert_eq!(
+        clock.sample(&mut tracker, 1100, 1000, None),
+        throughput(1000, 1100, None)
+    );
+}
+
+#[test]
+fn samples_outside_of_the_window_and_before_a_reset_are_dropped() {
+    let (clock, mut tracker) = (Clock::new(), ThroughputTracker::default());
+    clock.sample(&mut tracker, 0, 0, None);
+    clock.sample(&mut tracker, 5_000, 100, None);
+    assert_eq!(
+        clock.sample(&mut tracker, 12_000, 200, None),
+        throughput(100, 7_000, None),
+        "samples older than 10s are dropped"
+    );
+
+    assert_eq!(
+        clock.sample(&mut tracker, 13_000, 50, None),
+        None,
+        "progress going backwards starts over"
+    );
+    assert_eq!(
+        clock.sample(&mut tracker, 14_000, 60, None),
+        throughput(10, 1000, None)
+    );
+}
+
+#[test]
+fn etas_too_far_in_the_future_are_unknown() {
+    let done_at = 3 << 62;
+    let (clock, mut tracker) = (Clock::new(), ThroughputTracker::default());
+    clock.sample(&mut tracker, 0, 0, Some(done_at));
+    assert_eq!(
+        clock.sample(&mut tracker, 1000, 1, Some(done_at)),
+        throughput(1, 1000, None),
+        "the remaining time doesn't fit into a SystemTime"
+    );
+
+    let (clock, mut tracker) = (Clock::new(), ThroughputTracker::default());
+    clock.sample(&mut tracker, 0, 0, Some(ProgressStep::MAX));
+    assert_eq!(
+        clock.sample(&mut tracker, 10_000, 1, Some(ProgressStep::MAX)),
+        throughput(1, 10_000, None),
+        "the remaining time doesn't fit into a Duration"
+    );
+}
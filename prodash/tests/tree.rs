@@ -0,0 +1,46 @@
+use prodash::tree::{Item, Key, Value};
+
+fn names_and_levels(tree: &prodash::Tree) -> Vec<(String, usize)> {
+    let mut entries: Vec<(Key, Value)> = Vec::new();
+    tree.sorted_snapshot(&mut entries);
+    entries
+        .into_iter()
+        .map(|(key, value)| (value.name, key.level()))
+        .collect()
+}
+
+#[test]
+fn deep_hierarchies_are_sorted_with_each_item_before_its_children() {
+    let tree = prodash::Tree::new();
+    let mut first = tree.add_child("1");
+    let _second = tree.add_child("2");
+
+    let mut chain: Vec<Item> = Vec::new();
+    let mut parent = first.add_child("1.1");
+    for level in 3..=8 {
+        let child = parent.add_child(format!("level {}", level));
+        chain.push(parent);
+        parent = child;
+    }
+    let _sibling_of_level_4 = chain[1].add_child("level 4, second");
+    let _sibling_of_level_8 = chain[5].add_child("level 8, second");
+    let _last_child_of_first = first.add_child("1.2");
+
+    assert_eq!(
+        names_and_levels(&tree),
+        vec![
+            ("1".into(), 1),
+            ("1.1".into(), 2),
+            ("level 3".into(), 3),
+            ("level 4".into(), 4),
+            ("level 5".into(), 5),
+            ("level 6".into(), 6),
+            ("level 7".into(), 7),
+            ("level 8".into(), 8),
+            ("level 8, second".into(), 8),
+            ("level 4, second".into(), 4),
+            ("1.2".into(), 2),
+            ("2".into(), 1),
+        ]
+    );
+}
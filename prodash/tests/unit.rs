@@ -0,0 +1,85 @@
+use prodash::{
+    tree::ProgressStep,
+    unit::{self, DisplayValue, Scale, Unit, UnitDisplay},
+};
+use std::fmt;
+
+fn display(unit: &Unit, value: ProgressStep, upper_bound: Option<ProgressStep>) -> String {
+    unit.display(value, upper_bound).to_string()
+}
+
+#[test]
+fn bytes_are_scaled_until_the_largest_unit() {
+    let binary = Unit::Bytes(Scale::Binary);
+    assert_eq!(display(&binary, 0, None), "0 B");
+    assert_eq!(display(&binary, 1023, None), "1023 B");
+    assert_eq!(display(&binary, 1024, None), "1.0 KiB");
+    assert_eq!(
+        display(&binary, 5 * 1024 * 1024 * 1024, None),
+        "5.0 GiB",
+        "values beyond 4 GiB are supported"
+    );
+    assert_eq!(display(&binary, ProgressStep::MAX, None), "16.0 EiB");
+
+    let decimal = Unit::Bytes(Scale::Decimal);
+    assert_eq!(display(&decimal, 999, Some(1000)), "999 B / 1.0 kB");
+    assert_eq!(display(&decimal, 1_250_000, None), "1.2 MB");
+}
+
+#[test]
+fn durations_are_displayed_as_seconds() {
+    assert_eq!(display(&Unit::Duration, 0, None), "0s");
+    assert_eq!(display(&Unit::Duration, 90, Some(300)), "1m 30s / 5m");
+    assert_eq!(display(&Unit::Duration, 86_400 + 1, None), "1day 1s");
+}
+
+#[test]
+fn labels_follow_the_values() {
+    let items = unit::label("items");
+    assert_eq!(display(&items, 3, None), "3 items");
+    assert_eq!(display(&items, 0, Some(0)), "0 / 0 items");
+    assert_eq!(display(&unit::label(""), 1, None), "1 ");
+}
+
+#[test]
+fn values_without_unit_are_plain_numbers() {
+    assert_eq!(UnitDisplay::new(None, 3, None).to_string(), "3");
+    assert_eq!(UnitDisplay::new(None, 3, Some(10)).to_string(), "3 / 10");
+}
+
+struct Percent;
+
+impl DisplayValue for Percent {
+    fn display_current_value(
+        &self,
+        w: &mut dyn fmt::Write,
+        value: ProgressStep,
+        upper_bound: Option<ProgressStep>,
+    ) -> fmt::Result {
+        match upper_bound {
+            Some(upper_bound) if upper_bound > 0 => write!(w, "{}%", value * 100 / upper_bound),
+            _ => write!(w, "{}", value),
+        }
+    }
+
+    fn display_unit(&self, w: &mut dyn fmt::Write, _value: ProgressStep) -> fmt::Result {
+        w.write_str(" done")
+    }
+}
+
+#[test]
+fn custom_units_control_each_part() {
+    let percent = unit::custom(Percent);
+    assert_eq!(display(&percent, 5, Some(20)), "25% / 20 done");
+    assert_eq!(display(&percent, 5, Some(0)), "5 / 0 done");
+    assert_eq!(display(&percent, 5, None), "5 done");
+}
+
+#[test]
+fn units_are_equal_if_they_display_alike() {
+    let percent = unit::custom(Percent);
+    assert_eq!(percent, percent.clone());
+    assert_ne!(percent, unit::custom(Percent));
+    assert_eq!(Unit::from("items"), unit::label(String::from("items")));
+    assert_ne!(Unit::Bytes(Scale::Binary), Unit::Bytes(Scale::Decimal));
+}
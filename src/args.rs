@@ -21,6 +21,35 @@ pub struct Parsed {
     pub sub: Option<SubCommands>,
 }
 
+impl Parsed {
+    /// Like `from_args()`, but also exits with an error on combinations of arguments clap can't reject by itself.
+    pub fn from_args_checked() -> Self {
+        Self::from_args().check().unwrap_or_else(|err| err.exit())
+    }
+
+    fn check(self) -> Result<Self, clap::Error> {
+        #[cfg(feature = "mine")]
+        {
+            if let Some(SubCommands::Mine {
+                repository,
+                time_limit,
+                cmd: Some(_),
+                ..
+            }) = &self.sub
+            {
+                if repository.is_some() || time_limit.is_some() {
+                    return Err(clap::Error::with_description(
+                        "The arguments '--repository' and '--time-limit' of 'mine' cannot be used with a subcommand. \
+                         Pass them to the subcommand instead, like 'mine run -r REPO -t LIMIT'.",
+                        clap::ErrorKind::ArgumentConflict,
+                    ));
+                }
+            }
+        }
+        Ok(self)
+    }
+}
+
 #[derive(Debug, StructOpt)]
 pub enum SubCommands {
     /// show all recently changed crates
@@ -55,17 +84,103 @@ pub enum SubCommands {
         output_format: OutputKind,
     },
     /// Mine crates.io in an incorruptible and resumable fashion
+    ///
+    /// Without a subcommand, 'run' is assumed, which keeps invocations like 'crates mine [-r REPO] [-t LIMIT] <db-path>' working.
     #[cfg(feature = "mine")]
     #[structopt(display_order = 2)]
     Mine {
+        /// Path to the possibly existing database. It's used to persist all mining results.
+        #[structopt(long, short = "d", default_value = "criner.db")]
+        db_path: PathBuf,
+        /// Path to the possibly existing database, overriding --db-path.
+        #[structopt(name = "DB_PATH")]
+        positional_db_path: Option<PathBuf>,
+        #[structopt(short = "r", long, name = "REPO")]
+        /// Path to the possibly existing crates.io repository clone. Can only be used without a subcommand.
+        repository: Option<PathBuf>,
+        /// The amount of time we can take for the computation. Specified in humantime, like 10s, 5min, or 2h, or '3h 2min 2s'.
+        /// Can only be used without a subcommand.
+        #[structopt(long, short = "t")]
+        time_limit: Option<humantime::Duration>,
+        #[structopt(subcommand)]
+        cmd: Option<MineCmd>,
+    },
+}
+
+#[cfg(feature = "mine")]
+#[derive(StructOpt, Debug)]
+pub enum MineCmd {
+    /// fetch changes from the crates.io index and store them in the database
+    Run {
         #[structopt(short = "r", long, name = "REPO")]
         /// Path to the possibly existing crates.io repository clone. If unset, it will be cloned to a temporary spot.
         repository: Option<PathBuf>,
         /// The amount of time we can take for the computation. Specified in humantime, like 10s, 5min, or 2h, or '3h 2min 2s'
         #[structopt(long, short = "t")]
         time_limit: Option<humantime::Duration>,
-        /// Path to the possibly existing database. It's used to persist all mining results.
-        db_path: PathBuf,
+    },
+    /// import download counts, owners, keywords and categories from a crates.io database dump
+    ///
+    /// The dump is a tarball as available at https://static.crates.io/db-dump.tar.gz, which has to be downloaded beforehand.
+    /// Only crates already in the database are considered. An interrupted import resumes where it left off.
+    ImportDump {
+        /// The amount of time we can take for the import. Specified in humantime, like 10s, 5min, or 2h, or '3h 2min 2s'
+        #[structopt(long, short = "t")]
+        time_limit: Option<humantime::Duration>,
+        /// Path to the possibly gzipped tarball with the database dump
+        dump_path: PathBuf,
+    },
+    /// show all crate versions depending on the given crate, grouped by kind of dependency
+    ///
+    /// This information is gathered by 'mine run', and is only complete once it ran at least once. Until then, this fails.
+    Rdeps {
+        /// The name of the crate whose dependents to show, like 'serde'
+        name: String,
+        #[structopt(long = "output", short = "o", possible_values = &OutputKind::variants(), default_value = "human")]
+        /// The type of output to produce
+        output_format: OutputKind,
+    },
+    /// verify that crates, crate versions and the context agree with each other, and that all values can be read
+    ///
+    /// The database is streamed, so this works in bounded memory even on a full-sized database.
+    Fsck {
+        /// rebuild crates and reverse dependencies from the stored crate versions if issues were found
+        #[structopt(long)]
+        repair: bool,
+        /// when repairing, also correct the context counts to match the stored crates and crate versions
+        ///
+        /// This rewrites the history of counts recorded per day, which can't be undone.
+        #[structopt(long, requires = "repair")]
+        repair_context: bool,
+    },
+    /// write a consistent snapshot of the database into a new database at the given path
+    ///
+    /// The database is locked while the backup is written, so 'mine run' can't run at the same time.
+    Backup {
+        /// The path at which to create the backup. It must not exist yet.
+        dest: PathBuf,
+    },
+    /// recreate the database from a backup made with 'mine backup'
+    ///
+    /// To protect existing data, the database must not exist yet.
+    Restore {
+        /// The path to the backup to restore from
+        backup: PathBuf,
+    },
+    /// rewrite the database to reclaim unused space, optionally migrating it to or from compression
+    Compact {
+        /// Set to 'true' to compress the database, and to 'false' to decompress it. If unset, it is kept as is.
+        #[structopt(long)]
+        compression: Option<bool>,
+    },
+    /// serve the database read-only via HTTP, answering with JSON
+    ///
+    /// Available endpoints are /crates/<name>, /crates/<name>/versions, /crates/<name>/rdeps and /context.
+    /// The database is locked while serving, so 'mine run' can't write to it - consider serving a backup instead.
+    Serve {
+        /// The address to listen on
+        #[structopt(long, short = "b", default_value = "127.0.0.1:8080")]
+        bind: String,
     },
 }
 
@@ -80,3 +195,59 @@ pub enum ListCmd {
         id: u32,
     },
 }
+
+#[cfg(all(test, feature = "mine"))]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Parsed, clap::Error> {
+        Parsed::from_iter_safe(std::iter::once("crates").chain(args.iter().copied()))
+            .and_then(Parsed::check)
+    }
+
+    fn mine(args: &[&str]) -> (PathBuf, Option<PathBuf>, Option<MineCmd>) {
+        match parse(args).unwrap().sub {
+            Some(SubCommands::Mine {
+                db_path,
+                positional_db_path,
+                cmd,
+                ..
+            }) => (db_path, positional_db_path, cmd),
+            other => panic!("expected the mine subcommand, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn mine_without_subcommand_keeps_accepting_the_database_as_positional_argument() {
+        let (_, positional_db_path, cmd) = mine(&["mine", "-r", "index", "-t", "1h", "my.db"]);
+        assert_eq!(positional_db_path, Some(PathBuf::from("my.db")));
+        assert!(cmd.is_none());
+    }
+
+    #[test]
+    fn mine_with_subcommand_uses_the_database_option() {
+        let (db_path, positional_db_path, cmd) = mine(&["mine", "-d", "my.db", "run", "-t", "1h"]);
+        assert_eq!(db_path, PathBuf::from("my.db"));
+        assert_eq!(positional_db_path, None);
+        assert!(matches!(
+            cmd,
+            Some(MineCmd::Run {
+                time_limit: Some(_),
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn mine_with_subcommand_rejects_arguments_meant_for_run() {
+        for args in &[
+            &["mine", "-r", "index", "run"][..],
+            &["mine", "-t", "1h", "import-dump", "db-dump.tar.gz"],
+        ] {
+            assert_eq!(
+                parse(args).unwrap_err().kind,
+                clap::ErrorKind::ArgumentConflict
+            );
+        }
+    }
+}
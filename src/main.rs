@@ -2,10 +2,18 @@
 
 #[macro_use]
 extern crate clap;
-#[cfg_attr(any(feature = "recent-changes", feature = "list"), macro_use)]
+#[cfg_attr(
+    any(feature = "recent-changes", feature = "list", feature = "mine"),
+    macro_use
+)]
 extern crate prettytable;
 #[cfg_attr(
-    any(feature = "list", feature = "recent-changes", feature = "search"),
+    any(
+        feature = "list",
+        feature = "recent-changes",
+        feature = "search",
+        feature = "mine"
+    ),
     macro_use
 )]
 extern crate quick_error;
@@ -26,7 +34,8 @@ use scmds::handle_interactive_search;
 use scmds::handle_recent_changes;
 #[cfg(feature = "list")]
 use scmds::{by_user, handle_list};
-use structopt::StructOpt;
+#[cfg(feature = "mine")]
+use scmds::{handle_fsck, handle_reverse_dependencies, handle_sizes};
 
 use crate::args::Parsed;
 use std::ops::Add;
@@ -34,7 +43,7 @@ use std::ops::Add;
 fn main() {
     env_logger::init();
     use args::SubCommands::*;
-    let args: Parsed = args::Parsed::from_args();
+    let args: Parsed = args::Parsed::from_args_checked();
 
     match args.sub {
         #[cfg(feature = "recent-changes")]
@@ -53,15 +62,57 @@ fn main() {
         Some(Search) => ok_or_exit(handle_interactive_search()),
         #[cfg(feature = "mine")]
         Some(Mine {
-            repository,
             db_path,
+            positional_db_path,
+            repository,
             time_limit,
-        }) => ok_or_exit(criner::run_blocking(
-            db_path,
-            repository
-                .unwrap_or_else(|| std::env::temp_dir().join("criner-crates-io-bare-index.git")),
-            time_limit.map(|d| std::time::SystemTime::now().add(*d)),
-        )),
+            cmd,
+        }) => {
+            use args::MineCmd::*;
+            let db_path = positional_db_path.unwrap_or(db_path);
+            match cmd.unwrap_or(Run {
+                repository,
+                time_limit,
+            }) {
+                Run {
+                    repository,
+                    time_limit,
+                } => ok_or_exit(criner::run_blocking(
+                    db_path,
+                    repository.unwrap_or_else(|| {
+                        std::env::temp_dir().join("criner-crates-io-bare-index.git")
+                    }),
+                    time_limit.map(|d| std::time::SystemTime::now().add(*d)),
+                )),
+                ImportDump {
+                    time_limit,
+                    dump_path,
+                } => ok_or_exit(criner::import_dump(
+                    db_path,
+                    dump_path,
+                    time_limit.map(|d| std::time::SystemTime::now().add(*d)),
+                )),
+                Rdeps {
+                    name,
+                    output_format,
+                } => ok_or_exit(handle_reverse_dependencies(db_path, name, output_format)),
+                Fsck {
+                    repair,
+                    repair_context,
+                } => ok_or_exit(handle_fsck(db_path, repair, repair_context)),
+                Backup { dest } => {
+                    ok_or_exit(handle_sizes(criner::backup(db_path, dest), "backup"))
+                }
+                Restore { backup } => {
+                    ok_or_exit(handle_sizes(criner::restore(backup, db_path), "restore"))
+                }
+                Compact { compression } => ok_or_exit(handle_sizes(
+                    criner::compact(db_path, compression),
+                    "compaction",
+                )),
+                Serve { bind } => ok_or_exit(criner::serve_blocking(db_path, bind)),
+            }
+        }
         None =>
         {
             #[cfg(feature = "search")]
@@ -0,0 +1,94 @@
+use super::error::Error;
+use crate::args::OutputKind;
+use prettytable::{format, Table};
+use std::{io, path::PathBuf};
+
+pub fn handle_reverse_dependencies(
+    db_path: PathBuf,
+    name: String,
+    output_format: OutputKind,
+) -> Result<(), Error> {
+    let rdeps = criner::reverse_dependencies(db_path, &name)?;
+    match output_format {
+        OutputKind::human => {
+            if !rdeps.is_empty() {
+                let table = {
+                    let mut t = Table::new();
+                    t.set_titles(row![
+                        b -> "Kind",
+                        b -> "Dependent",
+                        b -> "Version",
+                        b -> "Requirement",
+                        b -> "Optional",
+                        b -> "Yanked"
+                    ]);
+                    t.set_format(*format::consts::FORMAT_NO_LINESEP_WITH_TITLE);
+                    rdeps.iter().fold(t, |mut t, d| {
+                        t.add_row(row![
+                            d.kind.as_str(),
+                            d.dependent,
+                            d.dependent_version,
+                            d.required_version,
+                            d.optional,
+                            d.yanked
+                        ]);
+                        t
+                    })
+                };
+                table.print_tty(false);
+            }
+            Ok(())
+        }
+        OutputKind::json => serde_json::to_writer_pretty(io::stdout(), &rdeps).map_err(Into::into),
+    }
+}
+
+pub fn handle_fsck(db_path: PathBuf, repair: bool, repair_context: bool) -> Result<(), Error> {
+    let report = criner::fsck(db_path, repair, repair_context, |issue| {
+        eprintln!("{}", issue)
+    })?;
+    println!(
+        "Checked {} crates and {} crate versions, found {} issue(s)",
+        report.crates, report.crate_versions, report.issues
+    );
+    let remaining = if report.repaired {
+        if repair_context {
+            println!("Rebuilt crates, reverse dependencies and context from crate versions");
+            report.unrepairable_issues
+        } else {
+            println!("Rebuilt crates and reverse dependencies from crate versions");
+            report.unrepairable_issues + report.count_mismatches
+        }
+    } else {
+        report.issues
+    };
+    match remaining {
+        0 => Ok(()),
+        count => Err(Error::IssuesRemaining(count)),
+    }
+}
+
+fn format_bytes(bytes: u64) -> String {
+    const UNITS: &[&str] = &["B", "KiB", "MiB", "GiB", "TiB"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit + 1 < UNITS.len() {
+        value /= 1024.0;
+        unit += 1;
+    }
+    format!("{:.1}{}", value, UNITS[unit])
+}
+
+pub fn handle_sizes(
+    sizes: criner::error::Result<criner::Sizes>,
+    operation: &str,
+) -> Result<(), Error> {
+    let sizes = sizes?;
+    println!(
+        "Size before {}: {}, after: {}",
+        operation,
+        format_bytes(sizes.before),
+        format_bytes(sizes.after)
+    );
+    Ok(())
+}
@@ -0,0 +1,16 @@
+quick_error! {
+    #[derive(Debug)]
+    pub enum Error {
+        Criner(err: criner::error::Error) {
+            from()
+            cause(err)
+        }
+        Encode(err: serde_json::Error) {
+            from()
+            cause(err)
+        }
+        IssuesRemaining(count: usize) {
+            display("{} issue(s) remain in the database", count)
+        }
+    }
+}
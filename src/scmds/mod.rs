@@ -1,5 +1,7 @@
 #[cfg(feature = "list")]
 mod list;
+#[cfg(feature = "mine")]
+mod mine;
 #[cfg(feature = "recent-changes")]
 mod recents;
 #[cfg(feature = "search")]
@@ -7,6 +9,8 @@ mod search;
 
 #[cfg(feature = "list")]
 pub use self::list::{by_user, handle_list, Error as ListError};
+#[cfg(feature = "mine")]
+pub use self::mine::{handle_fsck, handle_reverse_dependencies, handle_sizes};
 #[cfg(feature = "recent-changes")]
 pub use self::recents::{handle_recent_changes, Error as RecentChangesError};
 #[cfg(feature = "search")]